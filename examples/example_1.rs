@@ -1,14 +1,38 @@
-use tinytui::{Color, HorizontalAlignment, Terminal, VerticalAlignment, Widget};
+use tinytui::{Color, Constraint, HorizontalAlignment, Terminal, VerticalAlignment, Widget};
 
 fn main() {
     let mut terminal = Terminal::try_new().unwrap();
 
-    let screen = terminal.area();
-    let (left, right) = screen.split_horizontally();
-    let (left_top, left_bottom) = left.split_vertically_at(0.7);
-    let (top, bottom) = right.split_vertically();
-    let (bottom_left, bottom_right) = bottom.split_horizontally();
-    let (bottom_right_top, bottom_right_bottom) = bottom_right.split_vertically();
+    // Neighbouring panes share the wall between them
+    let mut screen = terminal.area();
+    screen.set_merged_borders(true);
+
+    let mut grid = screen.grid(
+        &[
+            Constraint::Percentage(50),
+            Constraint::Percentage(20),
+            Constraint::Percentage(5),
+            Constraint::Fill(1),
+        ],
+        &[
+            Constraint::Percentage(50),
+            Constraint::Percentage(25),
+            Constraint::Fill(1),
+        ],
+    );
+    grid.set_areas(&[
+        "panel center center",
+        "panel left   right",
+        "table left   right",
+        "table left   list",
+    ]);
+
+    let left_top = grid.area("panel").unwrap();
+    let left_bottom = grid.area("table").unwrap();
+    let top = grid.area("center").unwrap();
+    let bottom_left = grid.area("left").unwrap();
+    let bottom_right_top = grid.area("right").unwrap();
+    let bottom_right_bottom = grid.area("list").unwrap();
 
     let mut text_1 = top.text(
         "Center".to_string(),
@@ -70,7 +94,7 @@ fn main() {
     text_3.render(&mut terminal);
     table.render(&mut terminal);
 
-    terminal.draw();
+    terminal.draw().unwrap();
 
     std::thread::sleep(std::time::Duration::from_secs(1))
 }
@@ -0,0 +1,336 @@
+use std::ops::{BitOr, BitOrAssign};
+
+/// The sides of a `Rectangle` that have a border, they can be combined with `|`.
+#[derive(Copy, Clone, PartialEq, Eq)]
+pub struct Borders(u8);
+
+impl Borders {
+    pub const NONE: Borders = Borders(0);
+    pub const TOP: Borders = Borders(1);
+    pub const RIGHT: Borders = Borders(1 << 1);
+    pub const BOTTOM: Borders = Borders(1 << 2);
+    pub const LEFT: Borders = Borders(1 << 3);
+    pub const ALL: Borders = Borders(0b1111);
+
+    pub fn contains(&self, other: Borders) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+
+    pub fn insert(&mut self, other: Borders) {
+        self.0 |= other.0
+    }
+
+    pub fn remove(&mut self, other: Borders) {
+        self.0 &= !other.0
+    }
+
+    // How many cells the side takes, to use them as arithmetic offsets
+    pub(crate) fn width(&self, side: Borders) -> usize {
+        self.contains(side) as usize
+    }
+}
+
+impl Default for Borders {
+    fn default() -> Self {
+        Borders::ALL
+    }
+}
+
+impl std::fmt::Debug for Borders {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        const NAMES: [(Borders, &str); 4] = [
+            (Borders::TOP, "TOP"),
+            (Borders::RIGHT, "RIGHT"),
+            (Borders::BOTTOM, "BOTTOM"),
+            (Borders::LEFT, "LEFT"),
+        ];
+
+        if self.is_empty() {
+            return write!(f, "NONE");
+        }
+
+        if *self == Borders::ALL {
+            return write!(f, "ALL");
+        }
+
+        let names: Vec<&str> = NAMES
+            .iter()
+            .filter(|(side, _)| self.contains(*side))
+            .map(|(_, name)| *name)
+            .collect();
+
+        write!(f, "{}", names.join(" | "))
+    }
+}
+
+impl BitOr for Borders {
+    type Output = Borders;
+
+    fn bitor(self, rhs: Borders) -> Borders {
+        Borders(self.0 | rhs.0)
+    }
+}
+
+impl BitOrAssign for Borders {
+    fn bitor_assign(&mut self, rhs: Borders) {
+        self.0 |= rhs.0
+    }
+}
+
+/// The lines the border is drawn with.
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Default)]
+pub enum BorderType {
+    /// `┌─┐`
+    #[default]
+    Plain,
+    /// `╭─╮`
+    Rounded,
+    /// `╔═╗`
+    Double,
+    /// `┏━┓`
+    Thick,
+    /// `┌╌┐`
+    Dashed,
+    /// `+-+`, for terminals and logs that can't show box drawing characters.
+    Ascii,
+}
+
+/// The glyphs of a `BorderType`, including the junctions where other lines meet the border, e.g.
+/// the column separators of a table.
+pub(crate) struct BorderSymbols {
+    pub(crate) top_left: &'static str,
+    pub(crate) top_right: &'static str,
+    pub(crate) bottom_left: &'static str,
+    pub(crate) bottom_right: &'static str,
+    pub(crate) horizontal: &'static str,
+    pub(crate) vertical: &'static str,
+    // A vertical line meeting the top border, `┬`
+    pub(crate) top_junction: &'static str,
+    pub(crate) bottom_junction: &'static str,
+    // A horizontal line meeting the left border, `├`
+    pub(crate) left_junction: &'static str,
+    pub(crate) right_junction: &'static str,
+    pub(crate) cross: &'static str,
+    // The thumb of the scrollbar drawn over the border
+    pub(crate) thumb: &'static str,
+}
+
+impl BorderType {
+    pub(crate) fn symbols(self) -> BorderSymbols {
+        const PLAIN: BorderSymbols = BorderSymbols {
+            top_left: "┌",
+            top_right: "┐",
+            bottom_left: "└",
+            bottom_right: "┘",
+            horizontal: "─",
+            vertical: "│",
+            top_junction: "┬",
+            bottom_junction: "┴",
+            left_junction: "├",
+            right_junction: "┤",
+            cross: "┼",
+            thumb: "█",
+        };
+
+        match self {
+            BorderType::Plain => PLAIN,
+            BorderType::Rounded => BorderSymbols {
+                top_left: "╭",
+                top_right: "╮",
+                bottom_left: "╰",
+                bottom_right: "╯",
+                ..PLAIN
+            },
+            BorderType::Double => BorderSymbols {
+                top_left: "╔",
+                top_right: "╗",
+                bottom_left: "╚",
+                bottom_right: "╝",
+                horizontal: "═",
+                vertical: "║",
+                top_junction: "╦",
+                bottom_junction: "╩",
+                left_junction: "╠",
+                right_junction: "╣",
+                cross: "╬",
+                ..PLAIN
+            },
+            BorderType::Thick => BorderSymbols {
+                top_left: "┏",
+                top_right: "┓",
+                bottom_left: "┗",
+                bottom_right: "┛",
+                horizontal: "━",
+                vertical: "┃",
+                top_junction: "┳",
+                bottom_junction: "┻",
+                left_junction: "┣",
+                right_junction: "┫",
+                cross: "╋",
+                ..PLAIN
+            },
+            BorderType::Dashed => BorderSymbols {
+                horizontal: "╌",
+                vertical: "╎",
+                ..PLAIN
+            },
+            BorderType::Ascii => BorderSymbols {
+                top_left: "+",
+                top_right: "+",
+                bottom_left: "+",
+                bottom_right: "+",
+                horizontal: "-",
+                vertical: "|",
+                top_junction: "+",
+                bottom_junction: "+",
+                left_junction: "+",
+                right_junction: "+",
+                cross: "+",
+                thumb: "#",
+            },
+        }
+    }
+}
+
+impl BorderSymbols {
+    // The glyph whose lines reach the sides of the cell, a lone line is drawn across it
+    fn joining(&self, sides: Borders) -> &'static str {
+        const TOP_BOTTOM: Borders = Borders(TOP | BOTTOM);
+        const LEFT_RIGHT: Borders = Borders(LEFT | RIGHT);
+
+        match sides.0 {
+            _ if LEFT_RIGHT.contains(sides) => self.horizontal,
+            _ if TOP_BOTTOM.contains(sides) => self.vertical,
+            s if s == RIGHT | BOTTOM => self.top_left,
+            s if s == LEFT | BOTTOM => self.top_right,
+            s if s == TOP | RIGHT => self.bottom_left,
+            s if s == TOP | LEFT => self.bottom_right,
+            s if s == TOP | BOTTOM | RIGHT => self.left_junction,
+            s if s == TOP | BOTTOM | LEFT => self.right_junction,
+            s if s == LEFT | RIGHT | BOTTOM => self.top_junction,
+            s if s == LEFT | RIGHT | TOP => self.bottom_junction,
+            _ => self.cross,
+        }
+    }
+}
+
+const TOP: u8 = Borders::TOP.0;
+const RIGHT: u8 = Borders::RIGHT.0;
+const BOTTOM: u8 = Borders::BOTTOM.0;
+const LEFT: u8 = Borders::LEFT.0;
+
+// The sides of the cell reached by the lines of every box drawing glyph we draw borders with
+const GLYPH_SIDES: [(&str, u8); 39] = [
+    ("─", LEFT | RIGHT),
+    ("│", TOP | BOTTOM),
+    ("┌", RIGHT | BOTTOM),
+    ("┐", LEFT | BOTTOM),
+    ("└", TOP | RIGHT),
+    ("┘", TOP | LEFT),
+    ("├", TOP | BOTTOM | RIGHT),
+    ("┤", TOP | BOTTOM | LEFT),
+    ("┬", LEFT | RIGHT | BOTTOM),
+    ("┴", LEFT | RIGHT | TOP),
+    ("┼", TOP | RIGHT | BOTTOM | LEFT),
+    ("╭", RIGHT | BOTTOM),
+    ("╮", LEFT | BOTTOM),
+    ("╰", TOP | RIGHT),
+    ("╯", TOP | LEFT),
+    ("╌", LEFT | RIGHT),
+    ("╎", TOP | BOTTOM),
+    ("═", LEFT | RIGHT),
+    ("║", TOP | BOTTOM),
+    ("╔", RIGHT | BOTTOM),
+    ("╗", LEFT | BOTTOM),
+    ("╚", TOP | RIGHT),
+    ("╝", TOP | LEFT),
+    ("╠", TOP | BOTTOM | RIGHT),
+    ("╣", TOP | BOTTOM | LEFT),
+    ("╦", LEFT | RIGHT | BOTTOM),
+    ("╩", LEFT | RIGHT | TOP),
+    ("╬", TOP | RIGHT | BOTTOM | LEFT),
+    ("━", LEFT | RIGHT),
+    ("┃", TOP | BOTTOM),
+    ("┏", RIGHT | BOTTOM),
+    ("┓", LEFT | BOTTOM),
+    ("┗", TOP | RIGHT),
+    ("┛", TOP | LEFT),
+    ("┣", TOP | BOTTOM | RIGHT),
+    ("┫", TOP | BOTTOM | LEFT),
+    ("┳", LEFT | RIGHT | BOTTOM),
+    ("┻", LEFT | RIGHT | TOP),
+    ("╋", TOP | RIGHT | BOTTOM | LEFT),
+];
+
+// The same for the ASCII borders, where '+' is any junction, so it's taken as reaching every side.
+// They are only border glyphs when drawing ASCII borders, anywhere else they are most likely text,
+// e.g. "a-b".
+const ASCII_GLYPH_SIDES: [(&str, u8); 3] = [
+    ("-", LEFT | RIGHT),
+    ("|", TOP | BOTTOM),
+    ("+", TOP | RIGHT | BOTTOM | LEFT),
+];
+
+/// The glyph to draw when a border glyph, `symbol`, is drawn over `existing` one: a junction
+/// reaching the sides of both, e.g. `│` over `─` is `┼`. Anything that is not a border glyph is
+/// replaced by `symbol`.
+pub(crate) fn merge<'a>(existing: &str, symbol: &'a str, border_type: BorderType) -> &'a str {
+    let ascii_glyphs: &[(&str, u8)] = if border_type == BorderType::Ascii {
+        &ASCII_GLYPH_SIDES
+    } else {
+        &[]
+    };
+
+    let sides = |glyph: &str| {
+        GLYPH_SIDES
+            .iter()
+            .chain(ascii_glyphs)
+            .find(|(other, _)| *other == glyph)
+            .map(|(_, sides)| *sides)
+    };
+
+    match (sides(existing), sides(symbol)) {
+        (Some(existing_sides), Some(sides)) => border_type
+            .symbols()
+            .joining(Borders(existing_sides | sides)),
+        _ => symbol,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn merged_glyphs() {
+        let merge_plain = |existing, symbol| merge(existing, symbol, BorderType::Plain);
+
+        assert_eq!(merge_plain("│", "─"), "┼");
+        assert_eq!(merge_plain("┐", "┌"), "┬");
+        assert_eq!(merge_plain("┘", "└"), "┴");
+        assert_eq!(merge_plain("│", "┌"), "├");
+        assert_eq!(merge_plain("┤", "├"), "┼");
+        assert_eq!(merge_plain("─", "─"), "─");
+        // What was there is not a border
+        assert_eq!(merge_plain("a", "┌"), "┌");
+        assert_eq!(merge_plain(" ", "│"), "│");
+        assert_eq!(merge_plain("█", "│"), "│");
+        assert_eq!(merge_plain("#", "│"), "│");
+        // Nor is ASCII, unless the borders are ASCII too
+        assert_eq!(merge_plain("-", "│"), "│");
+        assert_eq!(merge_plain("+", "─"), "─");
+        assert_eq!(merge("|", "═", BorderType::Double), "═");
+        assert_eq!(merge("│", "-", BorderType::Ascii), "+");
+        // Joined with the lines of the border being drawn
+        assert_eq!(merge("│", "═", BorderType::Double), "╬");
+        assert_eq!(merge("┐", "╭", BorderType::Rounded), "┬");
+        assert_eq!(merge("╯", "╰", BorderType::Rounded), "┴");
+        assert_eq!(merge("┌", "┌", BorderType::Rounded), "╭");
+        assert_eq!(merge("|", "+", BorderType::Ascii), "+");
+        assert_eq!(merge("-", "-", BorderType::Ascii), "-");
+    }
+}
@@ -0,0 +1,290 @@
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Color {
+    // User's terminal default color
+    Default,
+    Black,
+    Red,
+    Green,
+    Yellow,
+    Blue,
+    Magenta,
+    Cyan,
+    White,
+    BrightBlack,
+    BrightRed,
+    BrightGreen,
+    BrightYellow,
+    BrightBlue,
+    BrightMagenta,
+    BrightCyan,
+    BrightWhite,
+    // An entry of the 256 colors palette, the first 16 are the named colors above
+    Indexed(u8),
+    Rgb(u8, u8, u8),
+}
+
+/// How many colors the terminal is able to show. Colors the terminal does not support are replaced
+/// by the closest one it does.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum ColorSupport {
+    Basic16,
+    Indexed256,
+    TrueColor,
+}
+
+impl ColorSupport {
+    /// Guesses the color support from the environment. There is no reliable way of asking the
+    /// terminal, but the ones supporting 24-bit colors usually advertise it through 'COLORTERM'.
+    pub fn from_env() -> ColorSupport {
+        let colorterm = std::env::var("COLORTERM").unwrap_or_default();
+        let term = std::env::var("TERM").unwrap_or_default();
+
+        ColorSupport::from_variables(&colorterm, &term)
+    }
+
+    fn from_variables(colorterm: &str, term: &str) -> ColorSupport {
+        if colorterm == "truecolor" || colorterm == "24bit" {
+            ColorSupport::TrueColor
+        } else if term.contains("256color") {
+            ColorSupport::Indexed256
+        } else {
+            ColorSupport::Basic16
+        }
+    }
+}
+
+// The RGB values of the 16 named colors, as in xterm's default palette. Terminals let the user
+// change them, so this is only used to approximate colors when downsampling.
+const PALETTE: [(u8, u8, u8); 16] = [
+    (0, 0, 0),
+    (205, 0, 0),
+    (0, 205, 0),
+    (205, 205, 0),
+    (0, 0, 238),
+    (205, 0, 205),
+    (0, 205, 205),
+    (229, 229, 229),
+    (127, 127, 127),
+    (255, 0, 0),
+    (0, 255, 0),
+    (255, 255, 0),
+    (92, 92, 255),
+    (255, 0, 255),
+    (0, 255, 255),
+    (255, 255, 255),
+];
+
+// The intensity of each step of the 6x6x6 color cube of the 256 colors palette
+const CUBE_STEPS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+const NAMED: [Color; 16] = [
+    Color::Black,
+    Color::Red,
+    Color::Green,
+    Color::Yellow,
+    Color::Blue,
+    Color::Magenta,
+    Color::Cyan,
+    Color::White,
+    Color::BrightBlack,
+    Color::BrightRed,
+    Color::BrightGreen,
+    Color::BrightYellow,
+    Color::BrightBlue,
+    Color::BrightMagenta,
+    Color::BrightCyan,
+    Color::BrightWhite,
+];
+
+impl Color {
+    /// The SGR parameter that sets the color as the foreground, e.g. '31' for red.
+    pub(crate) fn foreground_parameter(&self, color_support: ColorSupport) -> String {
+        match self.downsample(color_support) {
+            Color::Default => "39".to_string(),
+            Color::Indexed(index) => format!("38;5;{index}"),
+            Color::Rgb(r, g, b) => format!("38;2;{r};{g};{b}"),
+            named => match named.named_index() {
+                Some(index @ 0..=7) => (30 + index).to_string(),
+                Some(index) => (90 + index - 8).to_string(),
+                None => unreachable!(),
+            },
+        }
+    }
+
+    /// The SGR parameter that sets the color as the background, e.g. '41' for red.
+    pub(crate) fn background_parameter(&self, color_support: ColorSupport) -> String {
+        match self.downsample(color_support) {
+            Color::Default => "49".to_string(),
+            Color::Indexed(index) => format!("48;5;{index}"),
+            Color::Rgb(r, g, b) => format!("48;2;{r};{g};{b}"),
+            named => match named.named_index() {
+                Some(index @ 0..=7) => (40 + index).to_string(),
+                Some(index) => (100 + index - 8).to_string(),
+                None => unreachable!(),
+            },
+        }
+    }
+
+    /// Replaces the color by the closest one the terminal is able to show.
+    fn downsample(self, color_support: ColorSupport) -> Color {
+        match (self, color_support) {
+            (Color::Indexed(index), ColorSupport::Basic16) if index < 16 => NAMED[index as usize],
+            (Color::Indexed(index), ColorSupport::Basic16) => {
+                Color::closest_named(Color::indexed_to_rgb(index))
+            }
+            (Color::Rgb(r, g, b), ColorSupport::Basic16) => Color::closest_named((r, g, b)),
+            (Color::Rgb(r, g, b), ColorSupport::Indexed256) => Color::closest_indexed((r, g, b)),
+            (color, _) => color,
+        }
+    }
+
+    fn named_index(&self) -> Option<u8> {
+        NAMED
+            .iter()
+            .position(|named| named == self)
+            .map(|index| index as u8)
+    }
+
+    fn indexed_to_rgb(index: u8) -> (u8, u8, u8) {
+        match index {
+            0..=15 => PALETTE[index as usize],
+            16..=231 => {
+                let index = index - 16;
+                (
+                    CUBE_STEPS[(index / 36) as usize],
+                    CUBE_STEPS[(index / 6 % 6) as usize],
+                    CUBE_STEPS[(index % 6) as usize],
+                )
+            }
+            // The grayscale ramp, from 8 to 238 in steps of 10
+            _ => {
+                let level = 8 + (index - 232) * 10;
+                (level, level, level)
+            }
+        }
+    }
+
+    fn closest_named(rgb: (u8, u8, u8)) -> Color {
+        let index = (0..16)
+            .min_by_key(|index| Color::distance(rgb, PALETTE[*index]))
+            .unwrap();
+
+        NAMED[index]
+    }
+
+    /// Picks the closest entry from the color cube and the grayscale ramp. The 16 named colors are
+    /// skipped since their actual values depend on the user's theme.
+    fn closest_indexed((r, g, b): (u8, u8, u8)) -> Color {
+        let closest_step = |value: u8| {
+            (0..6)
+                .min_by_key(|step| (CUBE_STEPS[*step] as i32 - value as i32).abs())
+                .unwrap() as u8
+        };
+        let cube = 16 + 36 * closest_step(r) + 6 * closest_step(g) + closest_step(b);
+
+        let average = (r as u16 + g as u16 + b as u16) / 3;
+        let gray = 232 + ((average.saturating_sub(3)) / 10).min(23) as u8;
+
+        let index = [cube, gray]
+            .into_iter()
+            .min_by_key(|index| Color::distance((r, g, b), Color::indexed_to_rgb(*index)))
+            .unwrap();
+
+        Color::Indexed(index)
+    }
+
+    fn distance(a: (u8, u8, u8), b: (u8, u8, u8)) -> u32 {
+        let square = |a: u8, b: u8| (a as i32 - b as i32).pow(2) as u32;
+
+        square(a.0, b.0) + square(a.1, b.1) + square(a.2, b.2)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn foreground(color: Color, color_support: ColorSupport) -> String {
+        color.foreground_parameter(color_support)
+    }
+
+    fn background(color: Color, color_support: ColorSupport) -> String {
+        color.background_parameter(color_support)
+    }
+
+    #[test]
+    fn sgr_parameters() {
+        let true_color = ColorSupport::TrueColor;
+
+        assert_eq!(foreground(Color::Default, true_color), "39");
+        assert_eq!(background(Color::Default, true_color), "49");
+        assert_eq!(foreground(Color::Black, true_color), "30");
+        assert_eq!(foreground(Color::White, true_color), "37");
+        assert_eq!(background(Color::Cyan, true_color), "46");
+        assert_eq!(foreground(Color::BrightBlack, true_color), "90");
+        assert_eq!(background(Color::BrightWhite, true_color), "107");
+        assert_eq!(foreground(Color::Indexed(208), true_color), "38;5;208");
+        assert_eq!(background(Color::Indexed(208), true_color), "48;5;208");
+        assert_eq!(foreground(Color::Rgb(1, 2, 3), true_color), "38;2;1;2;3");
+        assert_eq!(background(Color::Rgb(1, 2, 3), true_color), "48;2;1;2;3");
+    }
+
+    #[test]
+    fn downsample_to_256_colors() {
+        let support = ColorSupport::Indexed256;
+
+        assert_eq!(
+            Color::Rgb(255, 0, 0).downsample(support),
+            Color::Indexed(196)
+        );
+        assert_eq!(Color::Rgb(0, 0, 0).downsample(support), Color::Indexed(16));
+        assert_eq!(
+            Color::Rgb(255, 135, 0).downsample(support),
+            Color::Indexed(208)
+        );
+        assert_eq!(
+            Color::Rgb(128, 128, 128).downsample(support),
+            Color::Indexed(244)
+        );
+        assert_eq!(Color::Indexed(42).downsample(support), Color::Indexed(42));
+        assert_eq!(Color::Green.downsample(support), Color::Green);
+    }
+
+    #[test]
+    fn downsample_to_16_colors() {
+        let support = ColorSupport::Basic16;
+
+        assert_eq!(
+            Color::Rgb(250, 10, 10).downsample(support),
+            Color::BrightRed
+        );
+        assert_eq!(Color::Rgb(10, 10, 10).downsample(support), Color::Black);
+        assert_eq!(Color::Rgb(0, 190, 190).downsample(support), Color::Cyan);
+        assert_eq!(Color::Indexed(3).downsample(support), Color::Yellow);
+        assert_eq!(Color::Indexed(12).downsample(support), Color::BrightBlue);
+        assert_eq!(Color::Indexed(196).downsample(support), Color::BrightRed);
+        assert_eq!(Color::Indexed(255).downsample(support), Color::White);
+        assert_eq!(Color::Default.downsample(support), Color::Default);
+        assert_eq!(foreground(Color::Rgb(0, 0, 238), support), "34");
+    }
+
+    #[test]
+    fn color_support_from_variables() {
+        assert_eq!(
+            ColorSupport::from_variables("truecolor", "xterm-256color"),
+            ColorSupport::TrueColor
+        );
+        assert_eq!(
+            ColorSupport::from_variables("24bit", "xterm"),
+            ColorSupport::TrueColor
+        );
+        assert_eq!(
+            ColorSupport::from_variables("", "xterm-256color"),
+            ColorSupport::Indexed256
+        );
+        assert_eq!(
+            ColorSupport::from_variables("", "linux"),
+            ColorSupport::Basic16
+        );
+    }
+}
@@ -0,0 +1,683 @@
+//! Decoding of the raw bytes read from the tty into typed events.
+//!
+//! Terminals report keys as a mix of plain bytes, UTF-8 sequences and escape sequences, and the
+//! escape sequences differ between terminal emulators. Here we try to understand the sequences
+//! emitted by xterm (and the ones that copy it, like VTE) and the Linux console. Mouse events are
+//! only understood in the SGR extended mode (1006), which is the one `Terminal` enables.
+
+use std::ops::{BitOr, BitOrAssign};
+
+const ESC: u8 = 0x1b;
+
+// Some sequences, like the CSI ones, may be arbitrarily long. We give up on anything longer than
+// this, since it's surely garbage, to avoid accumulating input forever.
+const MAX_SEQUENCE_LENGTH: usize = 64;
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Event {
+    Key(KeyEvent),
+    Mouse(MouseEvent),
+    // The new width and height of the terminal
+    Resize(usize, usize),
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct KeyEvent {
+    pub code: KeyCode,
+    pub modifiers: KeyModifiers,
+}
+
+impl KeyEvent {
+    pub fn new(code: KeyCode, modifiers: KeyModifiers) -> KeyEvent {
+        KeyEvent { code, modifiers }
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct MouseEvent {
+    pub kind: MouseEventKind,
+    // The cell under the mouse pointer, 0-based like everything else in the library
+    pub column: usize,
+    pub row: usize,
+    pub modifiers: KeyModifiers,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum MouseEventKind {
+    Down(MouseButton),
+    Up(MouseButton),
+    // The mouse moved while the button was held
+    Drag(MouseButton),
+    // The mouse moved without any button held, not every terminal reports it
+    Moved,
+    ScrollUp,
+    ScrollDown,
+    ScrollLeft,
+    ScrollRight,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum MouseButton {
+    Left,
+    Middle,
+    Right,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum KeyCode {
+    Char(char),
+    Enter,
+    Tab,
+    // Shift + Tab, terminals report it as its own key
+    BackTab,
+    Backspace,
+    Esc,
+    Up,
+    Down,
+    Left,
+    Right,
+    Home,
+    End,
+    PageUp,
+    PageDown,
+    Insert,
+    Delete,
+    // Function keys, from F1 to F12
+    F(u8),
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Default)]
+pub struct KeyModifiers(u8);
+
+impl KeyModifiers {
+    pub const NONE: KeyModifiers = KeyModifiers(0);
+    pub const SHIFT: KeyModifiers = KeyModifiers(1);
+    pub const ALT: KeyModifiers = KeyModifiers(1 << 1);
+    pub const CTRL: KeyModifiers = KeyModifiers(1 << 2);
+
+    pub fn contains(&self, other: KeyModifiers) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+
+    /// Decodes the modifier parameter of xterm's sequences, e.g. the '5' in 'ESC [ 1 ; 5 A'.
+    /// The parameter is one plus a bitmask where shift is 1, alt is 2 and ctrl is 4.
+    fn from_parameter(parameter: u16) -> KeyModifiers {
+        let mask = parameter.saturating_sub(1);
+
+        let mut modifiers = KeyModifiers::NONE;
+        if mask & 1 != 0 {
+            modifiers |= KeyModifiers::SHIFT;
+        }
+        if mask & 2 != 0 {
+            modifiers |= KeyModifiers::ALT;
+        }
+        if mask & 4 != 0 {
+            modifiers |= KeyModifiers::CTRL;
+        }
+
+        modifiers
+    }
+}
+
+impl BitOr for KeyModifiers {
+    type Output = KeyModifiers;
+
+    fn bitor(self, rhs: KeyModifiers) -> KeyModifiers {
+        KeyModifiers(self.0 | rhs.0)
+    }
+}
+
+impl BitOrAssign for KeyModifiers {
+    fn bitor_assign(&mut self, rhs: KeyModifiers) {
+        self.0 |= rhs.0
+    }
+}
+
+/// The outcome of trying to decode the start of the input.
+#[derive(PartialEq, Eq, Debug)]
+pub(crate) enum Parsed {
+    // An event and how many bytes it used
+    Event(Event, usize),
+    // The input is the beginning of a sequence, we need to wait for more bytes
+    Incomplete,
+    // The first bytes can't be understood and must be discarded
+    Invalid(usize),
+}
+
+/// Decodes the first event in `input`.
+///
+/// The escape key is also the first byte of every escape sequence, so a lone ESC is ambiguous
+/// until we know that no more bytes are coming. The caller signals it through `timed_out`, after
+/// waiting a little without receiving anything else.
+pub(crate) fn parse_event(input: &[u8], timed_out: bool) -> Parsed {
+    match input.first() {
+        None => Parsed::Incomplete,
+        Some(&ESC) => parse_escape(input, timed_out),
+        Some(_) => parse_key(input),
+    }
+}
+
+fn parse_escape(input: &[u8], timed_out: bool) -> Parsed {
+    let parsed = match input.get(1) {
+        None => Parsed::Incomplete,
+        Some(b'[') => parse_csi(input),
+        Some(b'O') => parse_ss3(input),
+        // Terminals send ESC before the key when it's pressed together with Alt
+        Some(_) => match parse_event(&input[1..], timed_out) {
+            Parsed::Event(Event::Key(key), length) => Parsed::Event(
+                Event::Key(KeyEvent::new(key.code, key.modifiers | KeyModifiers::ALT)),
+                length + 1,
+            ),
+            Parsed::Incomplete => Parsed::Incomplete,
+            _ => key(KeyCode::Esc, KeyModifiers::NONE, 1),
+        },
+    };
+
+    // Nothing else arrived, so it was the escape key followed by something else
+    match parsed {
+        Parsed::Incomplete if timed_out => key(KeyCode::Esc, KeyModifiers::NONE, 1),
+        parsed => parsed,
+    }
+}
+
+/// Control Sequence Introducer, 'ESC [' followed by parameters and a final byte.
+fn parse_csi(input: &[u8]) -> Parsed {
+    // The Linux console reports F1 to F5 as 'ESC [ [ A' to 'ESC [ [ E'
+    if input.get(2) == Some(&b'[') {
+        return match input.get(3) {
+            None => Parsed::Incomplete,
+            Some(&byte @ b'A'..=b'E') => key(KeyCode::F(byte - b'A' + 1), KeyModifiers::NONE, 4),
+            Some(_) => Parsed::Invalid(4),
+        };
+    }
+
+    let mut end = 2;
+    loop {
+        match input.get(end) {
+            None if end >= MAX_SEQUENCE_LENGTH => return Parsed::Invalid(end),
+            None => return Parsed::Incomplete,
+            // Parameter and intermediate bytes
+            Some(0x20..=0x3f) => end += 1,
+            Some(0x40..=0x7e) => break,
+            Some(_) => return Parsed::Invalid(end),
+        }
+    }
+
+    let length = end + 1;
+
+    if input[2] == b'<' {
+        return parse_sgr_mouse(&input[3..end], input[end], length);
+    }
+
+    let Some(parameters) = parse_parameters(&input[2..end]) else {
+        return Parsed::Invalid(length);
+    };
+    let modifiers = KeyModifiers::from_parameter(parameters.get(1).copied().unwrap_or(1));
+
+    let code = match input[end] {
+        b'A' => KeyCode::Up,
+        b'B' => KeyCode::Down,
+        b'C' => KeyCode::Right,
+        b'D' => KeyCode::Left,
+        b'H' => KeyCode::Home,
+        b'F' => KeyCode::End,
+        b'P' => KeyCode::F(1),
+        b'Q' => KeyCode::F(2),
+        b'R' => KeyCode::F(3),
+        b'S' => KeyCode::F(4),
+        b'Z' => return key(KeyCode::BackTab, KeyModifiers::SHIFT, length),
+        b'~' => match parameters.first().copied().unwrap_or(0) {
+            1 | 7 => KeyCode::Home,
+            2 => KeyCode::Insert,
+            3 => KeyCode::Delete,
+            4 | 8 => KeyCode::End,
+            5 => KeyCode::PageUp,
+            6 => KeyCode::PageDown,
+            number @ 11..=15 => KeyCode::F(number as u8 - 10),
+            number @ 17..=21 => KeyCode::F(number as u8 - 11),
+            number @ 23..=24 => KeyCode::F(number as u8 - 12),
+            _ => return Parsed::Invalid(length),
+        },
+        _ => return Parsed::Invalid(length),
+    };
+
+    key(code, modifiers, length)
+}
+
+/// SGR extended mouse reports, 'ESC [ < button ; column ; row' followed by 'M' for presses and
+/// motion, or 'm' for releases.
+fn parse_sgr_mouse(parameters: &[u8], last: u8, length: usize) -> Parsed {
+    let Some(&[button, column, row]) = parse_parameters(parameters).as_deref() else {
+        return Parsed::Invalid(length);
+    };
+
+    // The lower two bits are the button, the others flag modifiers, motion and the scroll wheel
+    let pressed = match button & 0b11 {
+        0 => Some(MouseButton::Left),
+        1 => Some(MouseButton::Middle),
+        2 => Some(MouseButton::Right),
+        _ => None,
+    };
+    let motion = button & 32 != 0;
+    let wheel = button & 64 != 0;
+
+    let kind = match (wheel, motion, last, pressed) {
+        (true, _, _, _) => match button & 0b11 {
+            0 => MouseEventKind::ScrollUp,
+            1 => MouseEventKind::ScrollDown,
+            2 => MouseEventKind::ScrollLeft,
+            _ => MouseEventKind::ScrollRight,
+        },
+        (false, true, _, Some(pressed)) => MouseEventKind::Drag(pressed),
+        (false, true, _, None) => MouseEventKind::Moved,
+        (false, false, b'M', Some(pressed)) => MouseEventKind::Down(pressed),
+        (false, false, b'm', Some(pressed)) => MouseEventKind::Up(pressed),
+        _ => return Parsed::Invalid(length),
+    };
+
+    let mut modifiers = KeyModifiers::NONE;
+    if button & 4 != 0 {
+        modifiers |= KeyModifiers::SHIFT;
+    }
+    if button & 8 != 0 {
+        modifiers |= KeyModifiers::ALT;
+    }
+    if button & 16 != 0 {
+        modifiers |= KeyModifiers::CTRL;
+    }
+
+    Parsed::Event(
+        Event::Mouse(MouseEvent {
+            kind,
+            // The terminal coordinates are 1-based
+            column: (column as usize).saturating_sub(1),
+            row: (row as usize).saturating_sub(1),
+            modifiers,
+        }),
+        length,
+    )
+}
+
+/// Single Shift 3, 'ESC O' followed by a single byte. Used for the cursor keys when the terminal is
+/// in application mode and for F1 to F4.
+fn parse_ss3(input: &[u8]) -> Parsed {
+    let Some(&byte) = input.get(2) else {
+        return Parsed::Incomplete;
+    };
+
+    // Some terminals send the modifiers between the 'O' and the key, as in 'ESC O 5 P'
+    let (modifiers, byte, length) = match byte {
+        b'0'..=b'9' => match input.get(3) {
+            None => return Parsed::Incomplete,
+            Some(&key) => (KeyModifiers::from_parameter((byte - b'0') as u16), key, 4),
+        },
+        _ => (KeyModifiers::NONE, byte, 3),
+    };
+
+    let code = match byte {
+        b'A' => KeyCode::Up,
+        b'B' => KeyCode::Down,
+        b'C' => KeyCode::Right,
+        b'D' => KeyCode::Left,
+        b'H' => KeyCode::Home,
+        b'F' => KeyCode::End,
+        b'M' => KeyCode::Enter,
+        b'P' => KeyCode::F(1),
+        b'Q' => KeyCode::F(2),
+        b'R' => KeyCode::F(3),
+        b'S' => KeyCode::F(4),
+        _ => return Parsed::Invalid(length),
+    };
+
+    key(code, modifiers, length)
+}
+
+/// Parses the ';' separated numeric parameters of a CSI sequence, an empty parameter is 0.
+fn parse_parameters(bytes: &[u8]) -> Option<Vec<u16>> {
+    if bytes.is_empty() {
+        return Some(Vec::new());
+    }
+
+    bytes
+        .split(|byte| *byte == b';')
+        .map(|parameter| {
+            parameter.iter().try_fold(0u16, |number, byte| match byte {
+                b'0'..=b'9' => number.checked_mul(10)?.checked_add((byte - b'0') as u16),
+                _ => None,
+            })
+        })
+        .collect()
+}
+
+/// Keys that are not escape sequences: control characters and UTF-8 encoded characters.
+fn parse_key(input: &[u8]) -> Parsed {
+    let code = match input[0] {
+        b'\r' | b'\n' => KeyCode::Enter,
+        b'\t' => KeyCode::Tab,
+        0x7f | 0x08 => KeyCode::Backspace,
+        0x00 => return key(KeyCode::Char(' '), KeyModifiers::CTRL, 1),
+        byte @ 0x01..=0x1a => {
+            return key(
+                KeyCode::Char((byte - 0x01 + b'a') as char),
+                KeyModifiers::CTRL,
+                1,
+            )
+        }
+        byte @ 0x1c..=0x1f => {
+            return key(
+                KeyCode::Char((byte - 0x1c + b'4') as char),
+                KeyModifiers::CTRL,
+                1,
+            )
+        }
+        byte @ 0x20..=0x7e => KeyCode::Char(byte as char),
+        byte => {
+            let length = match byte {
+                0xc0..=0xdf => 2,
+                0xe0..=0xef => 3,
+                0xf0..=0xf7 => 4,
+                _ => return Parsed::Invalid(1),
+            };
+
+            if input.len() < length {
+                return Parsed::Incomplete;
+            }
+
+            match std::str::from_utf8(&input[..length]) {
+                Ok(character) => {
+                    let character = character.chars().next().unwrap();
+                    return key(KeyCode::Char(character), KeyModifiers::NONE, length);
+                }
+                Err(_) => return Parsed::Invalid(1),
+            }
+        }
+    };
+
+    key(code, KeyModifiers::NONE, 1)
+}
+
+fn key(code: KeyCode, modifiers: KeyModifiers, length: usize) -> Parsed {
+    Parsed::Event(Event::Key(KeyEvent::new(code, modifiers)), length)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NONE: KeyModifiers = KeyModifiers::NONE;
+    const SHIFT: KeyModifiers = KeyModifiers::SHIFT;
+    const ALT: KeyModifiers = KeyModifiers::ALT;
+    const CTRL: KeyModifiers = KeyModifiers::CTRL;
+    const CTRL_SHIFT: KeyModifiers = KeyModifiers(KeyModifiers::CTRL.0 | KeyModifiers::SHIFT.0);
+    const CTRL_ALT: KeyModifiers = KeyModifiers(KeyModifiers::CTRL.0 | KeyModifiers::ALT.0);
+
+    // (terminal, input, expected key, expected modifiers)
+    const KEYS: &[(&str, &[u8], KeyCode, KeyModifiers)] = &[
+        // Plain characters and control bytes, common to every terminal
+        ("any", b"a", KeyCode::Char('a'), NONE),
+        ("any", b"A", KeyCode::Char('A'), NONE),
+        ("any", b" ", KeyCode::Char(' '), NONE),
+        ("any", b"~", KeyCode::Char('~'), NONE),
+        ("any", b"\r", KeyCode::Enter, NONE),
+        ("any", b"\n", KeyCode::Enter, NONE),
+        ("any", b"\t", KeyCode::Tab, NONE),
+        ("any", b"\x7f", KeyCode::Backspace, NONE),
+        ("any", b"\x08", KeyCode::Backspace, NONE),
+        ("any", b"\x01", KeyCode::Char('a'), CTRL),
+        ("any", b"\x03", KeyCode::Char('c'), CTRL),
+        ("any", b"\x1a", KeyCode::Char('z'), CTRL),
+        ("any", b"\x00", KeyCode::Char(' '), CTRL),
+        ("any", b"\x1c", KeyCode::Char('4'), CTRL),
+        ("any", b"\x1f", KeyCode::Char('7'), CTRL),
+        // UTF-8
+        ("any", "é".as_bytes(), KeyCode::Char('é'), NONE),
+        ("any", "€".as_bytes(), KeyCode::Char('€'), NONE),
+        ("any", "日".as_bytes(), KeyCode::Char('日'), NONE),
+        ("any", "🦀".as_bytes(), KeyCode::Char('🦀'), NONE),
+        // Alt sends an ESC prefix
+        ("any", b"\x1ba", KeyCode::Char('a'), ALT),
+        ("any", b"\x1bA", KeyCode::Char('A'), ALT),
+        ("any", b"\x1b\x03", KeyCode::Char('c'), CTRL_ALT),
+        ("any", b"\x1b\x7f", KeyCode::Backspace, ALT),
+        ("any", b"\x1b\r", KeyCode::Enter, ALT),
+        ("any", "\x1bé".as_bytes(), KeyCode::Char('é'), ALT),
+        // xterm and VTE, normal cursor mode
+        ("xterm", b"\x1b[A", KeyCode::Up, NONE),
+        ("xterm", b"\x1b[B", KeyCode::Down, NONE),
+        ("xterm", b"\x1b[C", KeyCode::Right, NONE),
+        ("xterm", b"\x1b[D", KeyCode::Left, NONE),
+        ("xterm", b"\x1b[H", KeyCode::Home, NONE),
+        ("xterm", b"\x1b[F", KeyCode::End, NONE),
+        ("xterm", b"\x1b[2~", KeyCode::Insert, NONE),
+        ("xterm", b"\x1b[3~", KeyCode::Delete, NONE),
+        ("xterm", b"\x1b[5~", KeyCode::PageUp, NONE),
+        ("xterm", b"\x1b[6~", KeyCode::PageDown, NONE),
+        ("xterm", b"\x1b[Z", KeyCode::BackTab, SHIFT),
+        ("xterm", b"\x1bOP", KeyCode::F(1), NONE),
+        ("xterm", b"\x1bOQ", KeyCode::F(2), NONE),
+        ("xterm", b"\x1bOR", KeyCode::F(3), NONE),
+        ("xterm", b"\x1bOS", KeyCode::F(4), NONE),
+        ("xterm", b"\x1b[15~", KeyCode::F(5), NONE),
+        ("xterm", b"\x1b[17~", KeyCode::F(6), NONE),
+        ("xterm", b"\x1b[18~", KeyCode::F(7), NONE),
+        ("xterm", b"\x1b[19~", KeyCode::F(8), NONE),
+        ("xterm", b"\x1b[20~", KeyCode::F(9), NONE),
+        ("xterm", b"\x1b[21~", KeyCode::F(10), NONE),
+        ("xterm", b"\x1b[23~", KeyCode::F(11), NONE),
+        ("xterm", b"\x1b[24~", KeyCode::F(12), NONE),
+        // xterm and VTE, with modifiers
+        ("xterm", b"\x1b[1;2A", KeyCode::Up, SHIFT),
+        ("xterm", b"\x1b[1;3B", KeyCode::Down, ALT),
+        ("xterm", b"\x1b[1;5C", KeyCode::Right, CTRL),
+        ("xterm", b"\x1b[1;6D", KeyCode::Left, CTRL_SHIFT),
+        ("xterm", b"\x1b[1;5H", KeyCode::Home, CTRL),
+        ("xterm", b"\x1b[1;2F", KeyCode::End, SHIFT),
+        ("xterm", b"\x1b[3;5~", KeyCode::Delete, CTRL),
+        ("xterm", b"\x1b[5;3~", KeyCode::PageUp, ALT),
+        ("xterm", b"\x1b[1;2P", KeyCode::F(1), SHIFT),
+        ("xterm", b"\x1b[1;5S", KeyCode::F(4), CTRL),
+        ("xterm", b"\x1b[15;2~", KeyCode::F(5), SHIFT),
+        ("xterm", b"\x1b[24;7~", KeyCode::F(12), CTRL_ALT),
+        // xterm and VTE, application cursor mode
+        ("xterm", b"\x1bOA", KeyCode::Up, NONE),
+        ("xterm", b"\x1bOB", KeyCode::Down, NONE),
+        ("xterm", b"\x1bOC", KeyCode::Right, NONE),
+        ("xterm", b"\x1bOD", KeyCode::Left, NONE),
+        ("xterm", b"\x1bOH", KeyCode::Home, NONE),
+        ("xterm", b"\x1bOF", KeyCode::End, NONE),
+        ("xterm", b"\x1bOM", KeyCode::Enter, NONE),
+        ("xterm", b"\x1bO5P", KeyCode::F(1), CTRL),
+        // Linux console
+        ("linux", b"\x1b[[A", KeyCode::F(1), NONE),
+        ("linux", b"\x1b[[B", KeyCode::F(2), NONE),
+        ("linux", b"\x1b[[C", KeyCode::F(3), NONE),
+        ("linux", b"\x1b[[D", KeyCode::F(4), NONE),
+        ("linux", b"\x1b[[E", KeyCode::F(5), NONE),
+        ("linux", b"\x1b[1~", KeyCode::Home, NONE),
+        ("linux", b"\x1b[4~", KeyCode::End, NONE),
+        ("linux", b"\x1b[A", KeyCode::Up, NONE),
+        // rxvt style Home and End, also seen in some tmux setups
+        ("rxvt", b"\x1b[7~", KeyCode::Home, NONE),
+        ("rxvt", b"\x1b[8~", KeyCode::End, NONE),
+    ];
+
+    #[test]
+    fn parse_keys() {
+        for (terminal, input, code, modifiers) in KEYS {
+            let expected = Parsed::Event(Event::Key(KeyEvent::new(*code, *modifiers)), input.len());
+
+            assert_eq!(parse_event(input, false), expected, "{terminal}: {input:?}");
+        }
+    }
+
+    #[test]
+    fn parse_keys_followed_by_more_input() {
+        for (terminal, input, code, modifiers) in KEYS {
+            let mut input = input.to_vec();
+            let length = input.len();
+            input.extend_from_slice(b"\x1b[A");
+
+            let expected = Parsed::Event(Event::Key(KeyEvent::new(*code, *modifiers)), length);
+
+            assert_eq!(
+                parse_event(&input, false),
+                expected,
+                "{terminal}: {input:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn parse_incomplete_sequences() {
+        for input in [
+            &b"\x1b"[..],
+            b"\x1b[",
+            b"\x1b[1",
+            b"\x1b[1;",
+            b"\x1b[1;5",
+            b"\x1b[[",
+            b"\x1bO",
+            b"\x1bO5",
+            &"日".as_bytes()[..2],
+            &"🦀".as_bytes()[..3],
+        ] {
+            assert_eq!(parse_event(input, false), Parsed::Incomplete, "{input:?}");
+        }
+    }
+
+    #[test]
+    fn parse_escape_after_timeout() {
+        let escape = Parsed::Event(Event::Key(KeyEvent::new(KeyCode::Esc, NONE)), 1);
+
+        assert_eq!(parse_event(b"\x1b", true), escape);
+        assert_eq!(parse_event(b"\x1b[", true), escape);
+        assert_eq!(parse_event(b"\x1b[1;5", true), escape);
+        assert_eq!(parse_event(b"\x1bO", true), escape);
+        assert_eq!(
+            parse_event(b"\x1b\x1b", true),
+            Parsed::Event(Event::Key(KeyEvent::new(KeyCode::Esc, ALT)), 2)
+        );
+    }
+
+    // (input, expected kind, column, row, modifiers)
+    const MOUSE: &[(&[u8], MouseEventKind, usize, usize, KeyModifiers)] = &[
+        (
+            b"\x1b[<0;1;1M",
+            MouseEventKind::Down(MouseButton::Left),
+            0,
+            0,
+            NONE,
+        ),
+        (
+            b"\x1b[<0;10;5m",
+            MouseEventKind::Up(MouseButton::Left),
+            9,
+            4,
+            NONE,
+        ),
+        (
+            b"\x1b[<1;3;4M",
+            MouseEventKind::Down(MouseButton::Middle),
+            2,
+            3,
+            NONE,
+        ),
+        (
+            b"\x1b[<2;120;40M",
+            MouseEventKind::Down(MouseButton::Right),
+            119,
+            39,
+            NONE,
+        ),
+        (
+            b"\x1b[<2;120;40m",
+            MouseEventKind::Up(MouseButton::Right),
+            119,
+            39,
+            NONE,
+        ),
+        (
+            b"\x1b[<32;7;8M",
+            MouseEventKind::Drag(MouseButton::Left),
+            6,
+            7,
+            NONE,
+        ),
+        (
+            b"\x1b[<34;7;8M",
+            MouseEventKind::Drag(MouseButton::Right),
+            6,
+            7,
+            NONE,
+        ),
+        (b"\x1b[<35;7;8M", MouseEventKind::Moved, 6, 7, NONE),
+        (b"\x1b[<64;2;2M", MouseEventKind::ScrollUp, 1, 1, NONE),
+        (b"\x1b[<65;2;2M", MouseEventKind::ScrollDown, 1, 1, NONE),
+        (b"\x1b[<66;2;2M", MouseEventKind::ScrollLeft, 1, 1, NONE),
+        (b"\x1b[<67;2;2M", MouseEventKind::ScrollRight, 1, 1, NONE),
+        (
+            b"\x1b[<4;1;1M",
+            MouseEventKind::Down(MouseButton::Left),
+            0,
+            0,
+            SHIFT,
+        ),
+        (
+            b"\x1b[<8;1;1M",
+            MouseEventKind::Down(MouseButton::Left),
+            0,
+            0,
+            ALT,
+        ),
+        (
+            b"\x1b[<16;1;1M",
+            MouseEventKind::Down(MouseButton::Left),
+            0,
+            0,
+            CTRL,
+        ),
+        (b"\x1b[<81;1;1M", MouseEventKind::ScrollDown, 0, 0, CTRL),
+        (
+            b"\x1b[<48;1;1M",
+            MouseEventKind::Drag(MouseButton::Left),
+            0,
+            0,
+            CTRL,
+        ),
+    ];
+
+    #[test]
+    fn parse_mouse() {
+        for (input, kind, column, row, modifiers) in MOUSE {
+            let expected = Parsed::Event(
+                Event::Mouse(MouseEvent {
+                    kind: *kind,
+                    column: *column,
+                    row: *row,
+                    modifiers: *modifiers,
+                }),
+                input.len(),
+            );
+
+            assert_eq!(parse_event(input, false), expected, "{input:?}");
+        }
+
+        assert_eq!(parse_event(b"\x1b[<0;10", false), Parsed::Incomplete);
+        assert_eq!(parse_event(b"\x1b[<0;10M", false), Parsed::Invalid(8));
+        assert_eq!(parse_event(b"\x1b[<3;1;1M", false), Parsed::Invalid(9));
+    }
+
+    #[test]
+    fn parse_invalid_sequences() {
+        assert_eq!(parse_event(b"\x1b[99~", false), Parsed::Invalid(5));
+        assert_eq!(parse_event(b"\x1b[1;2y", false), Parsed::Invalid(6));
+        assert_eq!(parse_event(b"\x1bOz", false), Parsed::Invalid(3));
+        assert_eq!(parse_event(b"\x1b[[z", false), Parsed::Invalid(4));
+        assert_eq!(parse_event(b"\xff", false), Parsed::Invalid(1));
+        assert_eq!(parse_event(b"\xc3\x28", false), Parsed::Invalid(1));
+    }
+}
@@ -0,0 +1,226 @@
+use std::ops::Range;
+
+use crate::layout::{self, Constraint};
+use crate::{BorderType, Rectangle, Style};
+
+/// A `Rectangle` divided in rows and columns, where each part of the layout takes a cell or spans
+/// several of them.
+///
+/// ```text
+/// +------------------------+
+/// |         header         |
+/// +------------------------+
+/// +-------++---------------+
+/// |       ||               |
+/// |sidebar||     main      |
+/// |       ||               |
+/// +-------++---------------+
+/// ```
+pub struct Grid {
+    x: usize,
+    y: usize,
+    border_style: Style,
+    style: Style,
+    border_type: BorderType,
+    merged_borders: bool,
+    // Where each track starts, relative to the grid, and how long it is
+    rows: Vec<(usize, usize)>,
+    columns: Vec<(usize, usize)>,
+    // The cells each named area covers
+    areas: Vec<(String, Range<usize>, Range<usize>)>,
+}
+
+impl Grid {
+    pub(crate) fn new(area: Rectangle, rows: &[Constraint], columns: &[Constraint]) -> Grid {
+        Grid {
+            x: area.x,
+            y: area.y,
+            border_style: area.border_style,
+            style: area.style,
+            border_type: area.border_type,
+            merged_borders: area.merged_borders,
+            rows: layout::tracks(rows, area.height, area.merged_borders),
+            columns: layout::tracks(columns, area.width, area.merged_borders),
+            areas: Vec::new(),
+        }
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows.len()
+    }
+
+    pub fn columns(&self) -> usize {
+        self.columns.len()
+    }
+
+    /// The rectangle of a single cell.
+    pub fn cell(&self, row: usize, column: usize) -> Rectangle {
+        self.span(row, column, 1, 1)
+    }
+
+    /// The rectangle covering `row_span` rows and `column_span` columns, starting at the cell in
+    /// `row` and `column`.
+    pub fn span(
+        &self,
+        row: usize,
+        column: usize,
+        row_span: usize,
+        column_span: usize,
+    ) -> Rectangle {
+        assert!(
+            row_span > 0 && column_span > 0,
+            "a span covers at least one cell"
+        );
+        assert!(
+            row + row_span <= self.rows.len() && column + column_span <= self.columns.len(),
+            "the span goes past the grid"
+        );
+
+        let (y, _) = self.rows[row];
+        let (x, _) = self.columns[column];
+        let (last_y, last_height) = self.rows[row + row_span - 1];
+        let (last_x, last_width) = self.columns[column + column_span - 1];
+
+        Rectangle {
+            border_style: self.border_style,
+            style: self.style,
+            border_type: self.border_type,
+            merged_borders: self.merged_borders,
+            ..Rectangle::new(
+                None,
+                self.x + x,
+                self.y + y,
+                last_x + last_width - x,
+                last_y + last_height - y,
+            )
+        }
+    }
+
+    /// Names the areas of the grid from a template with one line for each row, and one name for
+    /// each column in it, separated by whitespace. Repeating a name makes the area span those
+    /// cells, which must form a rectangle, and `.` leaves the cell unnamed.
+    ///
+    /// ```text
+    /// header  header
+    /// sidebar main
+    /// ```
+    pub fn set_areas(&mut self, template: &[&str]) {
+        assert_eq!(
+            template.len(),
+            self.rows.len(),
+            "the template must have a line for each row"
+        );
+
+        let names: Vec<Vec<&str>> = template
+            .iter()
+            .map(|line| line.split_whitespace().collect())
+            .collect();
+
+        for line in &names {
+            assert_eq!(
+                line.len(),
+                self.columns.len(),
+                "the template must have a name for each column in {line:?}"
+            );
+        }
+
+        let mut areas: Vec<(String, Range<usize>, Range<usize>)> = Vec::new();
+        for (row, line) in names.iter().enumerate() {
+            for (column, name) in line.iter().enumerate() {
+                if *name == "." || areas.iter().any(|(area, _, _)| area == name) {
+                    continue;
+                }
+
+                // The first cell of an area is its top left corner, it spans as long as the name
+                // repeats to the right and below
+                let column_end = column
+                    + line[column..]
+                        .iter()
+                        .take_while(|other| *other == name)
+                        .count();
+                let row_end = row
+                    + names[row..]
+                        .iter()
+                        .take_while(|other| other[column] == *name)
+                        .count();
+
+                let cells = names
+                    .iter()
+                    .flatten()
+                    .filter(|other| *other == name)
+                    .count();
+                assert_eq!(
+                    cells,
+                    (row_end - row) * (column_end - column),
+                    "the area {name:?} is not a rectangle"
+                );
+                for other in &names[row..row_end] {
+                    assert!(
+                        other[column..column_end].iter().all(|other| other == name),
+                        "the area {name:?} is not a rectangle"
+                    );
+                }
+
+                areas.push((name.to_string(), row..row_end, column..column_end));
+            }
+        }
+
+        self.areas = areas;
+    }
+
+    /// The rectangle of an area named by `set_areas`.
+    pub fn area(&self, name: &str) -> Option<Rectangle> {
+        self.areas
+            .iter()
+            .find(|(area, _, _)| area == name)
+            .map(|(_, rows, columns)| {
+                self.span(rows.start, columns.start, rows.len(), columns.len())
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Widget;
+    use Constraint::*;
+
+    fn bounds(rectangle: Rectangle) -> (usize, usize, usize, usize) {
+        (rectangle.x, rectangle.y, rectangle.width, rectangle.height)
+    }
+
+    #[test]
+    fn cells_and_spans() {
+        let mut area = Rectangle::new(None, 2, 1, 40, 12);
+        area.set_border_type(BorderType::Double);
+        let grid = area.grid(&[Length(3), Fill(1)], &[Length(10), Fill(1), Fill(1)]);
+
+        assert_eq!((grid.rows(), grid.columns()), (2, 3));
+        assert_eq!(bounds(grid.cell(0, 0)), (2, 1, 10, 3));
+        assert_eq!(bounds(grid.cell(1, 2)), (27, 4, 15, 9));
+        assert_eq!(bounds(grid.span(0, 1, 2, 2)), (12, 1, 30, 12));
+        assert_eq!(bounds(grid.span(0, 0, 2, 3)), (2, 1, 40, 12));
+        assert_eq!(grid.cell(1, 1).border_type, BorderType::Double);
+    }
+
+    #[test]
+    fn named_areas() {
+        let area = Rectangle::new(None, 0, 0, 30, 10);
+        let mut grid = area.grid(&[Length(3), Fill(1), Length(1)], &[Length(10), Fill(1)]);
+        grid.set_areas(&["header  header", "sidebar main", ".       main"]);
+
+        assert_eq!(grid.area("header").map(bounds), Some((0, 0, 30, 3)));
+        assert_eq!(grid.area("sidebar").map(bounds), Some((0, 3, 10, 6)));
+        assert_eq!(grid.area("main").map(bounds), Some((10, 3, 20, 7)));
+        assert!(grid.area("footer").is_none());
+        assert!(grid.area(".").is_none());
+    }
+
+    #[test]
+    #[should_panic(expected = "not a rectangle")]
+    fn areas_must_be_rectangles() {
+        let area = Rectangle::new(None, 0, 0, 30, 10);
+        let mut grid = area.grid(&[Fill(1), Fill(1)], &[Fill(1), Fill(1)]);
+        grid.set_areas(&["a a", "a b"]);
+    }
+}
@@ -0,0 +1,409 @@
+/// The axis a `Rectangle` is split along.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Direction {
+    /// The parts are side by side, from left to right.
+    Horizontal,
+    /// The parts are stacked, from top to bottom.
+    Vertical,
+}
+
+/// Space around the content of a `Rectangle`, in cells, used for its padding and its margin.
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Default)]
+pub struct Spacing {
+    pub top: usize,
+    pub right: usize,
+    pub bottom: usize,
+    pub left: usize,
+}
+
+impl Spacing {
+    pub const ZERO: Spacing = Spacing::uniform(0);
+
+    /// The same space on every side.
+    pub const fn uniform(space: usize) -> Spacing {
+        Spacing {
+            top: space,
+            right: space,
+            bottom: space,
+            left: space,
+        }
+    }
+
+    /// `vertical` space above and below, and `horizontal` space on the left and on the right.
+    pub const fn symmetric(vertical: usize, horizontal: usize) -> Spacing {
+        Spacing {
+            top: vertical,
+            right: horizontal,
+            bottom: vertical,
+            left: horizontal,
+        }
+    }
+}
+
+/// How much space a part of the layout, e.g. a table column, takes.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Constraint {
+    /// Exactly this many cells.
+    Length(usize),
+    /// A percentage of the available space.
+    Percentage(u16),
+    /// A fraction of the available space, e.g. `Ratio(1, 3)` for a third.
+    Ratio(u32, u32),
+    /// At least this many cells, growing to take the space nobody else wants.
+    Min(usize),
+    /// At most this many cells.
+    Max(usize),
+    /// The space nobody else wants, shared between every `Fill` in proportion to their weights.
+    Fill(u16),
+}
+
+impl Constraint {
+    /// Splits the `available` space between the constraints, never going past it.
+    ///
+    /// Every constraint starts with the size it asks for, where the cells lost when rounding down
+    /// `Percentage` and `Ratio` go to the ones that lost the most. If they do not fit, the largest
+    /// sizes are shrunk first, leaving `Min` for last. If there is space left, it goes to the
+    /// `Fill` constraints or, when there is none, to the `Min` ones. Whatever can't be split evenly
+    /// goes to the first ones, one cell each.
+    pub fn resolve(constraints: &[Constraint], available: usize) -> Vec<usize> {
+        let mut sizes = Constraint::base_sizes(constraints, available);
+
+        let total: usize = sizes.iter().sum();
+
+        if total > available {
+            let floors: Vec<usize> = constraints
+                .iter()
+                .zip(&sizes)
+                .map(|(constraint, size)| match constraint {
+                    Constraint::Min(_) => *size,
+                    _ => 0,
+                })
+                .collect();
+
+            let excess = shrink(&mut sizes, &floors, total - available);
+            shrink(&mut sizes, &vec![0; floors.len()], excess);
+        } else {
+            let fill_weights: Vec<usize> = constraints
+                .iter()
+                .map(|constraint| match constraint {
+                    Constraint::Fill(weight) => *weight as usize,
+                    _ => 0,
+                })
+                .collect();
+
+            let weights = if fill_weights.iter().any(|weight| *weight > 0) {
+                fill_weights
+            } else {
+                constraints
+                    .iter()
+                    .map(|constraint| matches!(constraint, Constraint::Min(_)) as usize)
+                    .collect()
+            };
+
+            for (size, share) in sizes
+                .iter_mut()
+                .zip(distribute(available - total, &weights))
+            {
+                *size += share;
+            }
+        }
+
+        sizes
+    }
+
+    fn base_sizes(constraints: &[Constraint], available: usize) -> Vec<usize> {
+        // The exact size of the proportional constraints is `numerator / denominator`
+        let fractions: Vec<Option<(usize, usize)>> = constraints
+            .iter()
+            .map(|constraint| match *constraint {
+                Constraint::Percentage(percentage) => Some((available * percentage as usize, 100)),
+                Constraint::Ratio(numerator, denominator) => {
+                    assert!(denominator > 0, "a ratio can't have a zero denominator");
+                    Some((available * numerator as usize, denominator as usize))
+                }
+                _ => None,
+            })
+            .collect();
+
+        let mut sizes: Vec<usize> = constraints
+            .iter()
+            .zip(&fractions)
+            .map(|(constraint, fraction)| match (*constraint, fraction) {
+                (_, Some((numerator, denominator))) => numerator / denominator,
+                (Constraint::Length(length), _) => length,
+                (Constraint::Min(min), _) => min,
+                (Constraint::Max(max), _) => max,
+                _ => 0,
+            })
+            .collect();
+
+        // Each one lost less than a cell when rounded down, but together they may have lost a few
+        let remainders: Vec<(usize, f64)> = fractions
+            .iter()
+            .enumerate()
+            .filter_map(|(i, fraction)| {
+                fraction.map(|(numerator, denominator)| {
+                    (i, (numerator % denominator) as f64 / denominator as f64)
+                })
+            })
+            .collect();
+
+        // The epsilon covers the error of adding up the remainders, e.g. three thirds
+        let lost = (remainders
+            .iter()
+            .map(|(_, remainder)| remainder)
+            .sum::<f64>()
+            + 1e-9) as usize;
+
+        let mut by_remainder = remainders;
+        // Stable, so the first ones win the ties
+        by_remainder.sort_by(|(_, a), (_, b)| b.total_cmp(a));
+        for (i, _) in by_remainder.into_iter().take(lost) {
+            sizes[i] += 1;
+        }
+
+        sizes
+    }
+}
+
+/// Lays out one track for each constraint, e.g. the columns of a grid, as where each one starts and
+/// how long it is. They cover exactly the `length`, the space the constraints leave unused goes to
+/// the last one.
+///
+/// When `shared_walls` is set, each track overlaps the next one by a cell, so neighbours share the
+/// wall between them instead of drawing one each.
+pub(crate) fn tracks(
+    constraints: &[Constraint],
+    length: usize,
+    shared_walls: bool,
+) -> Vec<(usize, usize)> {
+    let overlap = shared_walls as usize;
+    // The shared cells are counted once for each track they belong to
+    let available = length + overlap * constraints.len().saturating_sub(1);
+
+    let mut sizes = Constraint::resolve(constraints, available);
+    let unused = available - sizes.iter().sum::<usize>();
+    if let Some(last) = sizes.last_mut() {
+        *last += unused;
+    }
+
+    let mut end: usize = 0;
+    sizes
+        .into_iter()
+        .map(|size| {
+            // Empty tracks have no wall to share, they sit where the next one starts. Those that
+            // didn't share one leave more than the length, so the last ones are cut short.
+            let start = if size > 0 {
+                end.saturating_sub(overlap)
+            } else {
+                end
+            };
+            let start = usize::min(start, length);
+            if size > 0 {
+                end = start + size;
+            }
+
+            (start, usize::min(size, length - start))
+        })
+        .collect()
+}
+
+/// Takes `excess` cells from the largest sizes, never going below their floors, so they end up as
+/// even as possible. Returns the excess that could not be taken.
+fn shrink(sizes: &mut [usize], floors: &[usize], excess: usize) -> usize {
+    // What cutting the sizes down to the level takes, the ones with a higher floor down to it
+    let taken = |level: usize| {
+        sizes
+            .iter()
+            .zip(floors)
+            .map(|(size, floor)| size.saturating_sub(usize::max(*floor, level)))
+            .fold(0, usize::saturating_add)
+    };
+
+    // The lowest level that does not take more than the excess
+    let mut level = 0;
+    let mut high = sizes.iter().copied().max().unwrap_or(0);
+    while level < high {
+        let middle = level + (high - level) / 2;
+        if taken(middle) <= excess {
+            high = middle;
+        } else {
+            level = middle + 1;
+        }
+    }
+
+    let mut excess = excess - taken(level);
+    for (size, floor) in sizes.iter_mut().zip(floors) {
+        *size = usize::min(*size, usize::max(*floor, level));
+    }
+
+    // What is left is less than a cell for each size at the level, it's taken from the last ones so
+    // the first columns are the last to lose space
+    for (size, floor) in sizes.iter_mut().zip(floors).rev() {
+        if excess == 0 {
+            break;
+        }
+
+        if *size == level && *floor < level {
+            *size -= 1;
+            excess -= 1;
+        }
+    }
+
+    excess
+}
+
+/// Splits `space` in proportion to the weights, the remainder goes to the first ones with weight.
+pub(crate) fn distribute(space: usize, weights: &[usize]) -> Vec<usize> {
+    let total_weight: usize = weights.iter().sum();
+
+    if total_weight == 0 {
+        return vec![0; weights.len()];
+    }
+
+    let mut shares: Vec<usize> = weights
+        .iter()
+        .map(|weight| space * weight / total_weight)
+        .collect();
+
+    let mut remainder = space - shares.iter().sum::<usize>();
+    for (share, weight) in shares.iter_mut().zip(weights) {
+        if remainder == 0 {
+            break;
+        }
+
+        if *weight > 0 {
+            *share += 1;
+            remainder -= 1;
+        }
+    }
+
+    shares
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::random::Random;
+    use Constraint::*;
+
+    #[test]
+    fn resolve_fits_the_available_space() {
+        assert_eq!(Constraint::resolve(&[Length(3), Length(4)], 10), [3, 4]);
+        assert_eq!(
+            Constraint::resolve(&[Percentage(50), Percentage(25)], 10),
+            [5, 2]
+        );
+        assert_eq!(Constraint::resolve(&[Length(3), Fill(1)], 10), [3, 7]);
+        assert_eq!(
+            Constraint::resolve(&[Fill(1), Fill(2), Fill(1)], 10),
+            [3, 5, 2]
+        );
+        assert_eq!(
+            Constraint::resolve(&[Min(2), Length(3), Min(1)], 10),
+            [4, 3, 3]
+        );
+        assert_eq!(Constraint::resolve(&[Min(2), Fill(1)], 10), [2, 8]);
+        assert_eq!(Constraint::resolve(&[Max(4), Length(2)], 10), [4, 2]);
+        assert_eq!(Constraint::resolve(&[], 10), Vec::<usize>::new());
+    }
+
+    #[test]
+    fn resolve_distributes_the_rounding_remainders() {
+        assert_eq!(
+            Constraint::resolve(&[Ratio(1, 3), Ratio(1, 3), Ratio(1, 3)], 10),
+            [4, 3, 3]
+        );
+        assert_eq!(
+            Constraint::resolve(&[Percentage(50), Percentage(50)], 11),
+            [6, 5]
+        );
+        // The cell goes to the one that lost the most when rounded down
+        assert_eq!(
+            Constraint::resolve(&[Percentage(22), Percentage(78)], 10),
+            [2, 8]
+        );
+        // Or the first one on a tie
+        assert_eq!(
+            Constraint::resolve(&[Percentage(25), Ratio(3, 4)], 10),
+            [3, 7]
+        );
+        // Less than a cell is lost in total, so nobody gets it
+        assert_eq!(Constraint::resolve(&[Percentage(33)], 10), [3]);
+        assert_eq!(Constraint::resolve(&[Length(2), Ratio(1, 2)], 10), [2, 5]);
+    }
+
+    #[test]
+    fn tracks_cover_the_length() {
+        assert_eq!(
+            tracks(&[Length(3), Fill(1), Length(2)], 10, false),
+            [(0, 3), (3, 5), (8, 2)]
+        );
+        assert_eq!(tracks(&[Length(3), Length(2)], 10, false), [(0, 3), (3, 7)]);
+        // Neighbours overlap on the wall they share
+        assert_eq!(
+            tracks(&[Length(3), Fill(1), Length(2)], 10, true),
+            [(0, 3), (2, 7), (8, 2)]
+        );
+        assert_eq!(tracks(&[Fill(1), Fill(1)], 9, true), [(0, 5), (4, 5)]);
+        assert_eq!(tracks(&[Length(0), Fill(1)], 4, true), [(0, 0), (0, 4)]);
+        assert_eq!(
+            tracks(&[Length(3), Length(0), Fill(1)], 8, true),
+            [(0, 3), (3, 0), (2, 6)]
+        );
+    }
+
+    #[test]
+    fn resolve_shrinks_when_there_is_not_enough_space() {
+        assert_eq!(Constraint::resolve(&[Length(8), Length(4)], 10), [6, 4]);
+        assert_eq!(Constraint::resolve(&[Length(6), Length(6)], 10), [5, 5]);
+        assert_eq!(Constraint::resolve(&[Min(6), Length(6)], 10), [6, 4]);
+        assert_eq!(Constraint::resolve(&[Min(6), Min(6)], 10), [5, 5]);
+        assert_eq!(Constraint::resolve(&[Length(6), Max(20)], 10), [5, 5]);
+        assert_eq!(Constraint::resolve(&[Length(3), Fill(1)], 2), [2, 0]);
+        assert_eq!(Constraint::resolve(&[Ratio(3, 2), Length(2)], 10), [8, 2]);
+        assert_eq!(
+            Constraint::resolve(&[Length(usize::MAX / 2), Min(3), Length(4)], 10),
+            [4, 3, 3]
+        );
+    }
+
+    #[test]
+    fn shrink_takes_from_the_last_of_the_largest() {
+        // One cell at a time, the way it reads
+        fn shrink_cell_by_cell(sizes: &mut [usize], floors: &[usize], mut excess: usize) -> usize {
+            while excess > 0 {
+                let largest = (0..sizes.len())
+                    .filter(|i| sizes[*i] > floors[*i])
+                    .max_by_key(|i| sizes[*i]);
+
+                let Some(largest) = largest else {
+                    break;
+                };
+
+                sizes[largest] -= 1;
+                excess -= 1;
+            }
+
+            excess
+        }
+
+        let mut random = Random::new(0x2545_f491_4f6c_dd1d);
+        for _ in 0..2000 {
+            let count = random.below(6);
+            let sizes: Vec<usize> = (0..count).map(|_| random.below(20)).collect();
+            let floors: Vec<usize> = (0..count).map(|_| random.below(10)).collect();
+            let excess = random.below(60);
+
+            let mut expected = sizes.clone();
+            let expected_excess = shrink_cell_by_cell(&mut expected, &floors, excess);
+
+            let mut shrunk = sizes.clone();
+            assert_eq!(
+                (shrink(&mut shrunk, &floors, excess), shrunk),
+                (expected_excess, expected),
+                "{sizes:?} {floors:?} {excess}"
+            );
+        }
+    }
+}
@@ -2,64 +2,318 @@
 //! It's inspired in the tiling window manager system, where the user always have the whole screen
 //! covered and it just splits it between different widgets.
 
-use std::io::{stdout, Read, Write};
+use std::io::{stdout, Read, Stdout, Write};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
 use std::{mem::MaybeUninit, os::fd::AsRawFd};
 
 use libc::termios as Termios;
 
-// TODO: Introduce the concept of vertical scrolling
-// TODO: Add diff-rendering instead of clearing and rendering everything back again on every tick
+mod border;
+mod color;
+mod event;
+mod grid;
+mod layout;
+#[cfg(test)]
+mod random;
+mod selection;
+mod snapshot;
+mod sort;
+mod style;
+mod width;
+mod wrap;
+
+pub use border::{BorderType, Borders};
+pub use color::{Color, ColorSupport};
+use event::Parsed;
+pub use event::{Event, KeyCode, KeyEvent, KeyModifiers, MouseButton, MouseEvent, MouseEventKind};
+pub use grid::Grid;
+pub use layout::{Constraint, Direction, Spacing};
+use selection::Selection;
+pub use snapshot::assert_snapshot;
+pub use sort::SortDirection;
+pub use style::{Modifier, Style};
+use width::Graphemes;
+pub use wrap::WrapMode;
+use wrap::WrappedLines;
+
+// How long we wait for the rest of an escape sequence before deciding that the user just pressed
+// the escape key
+const ESCAPE_TIMEOUT: Duration = Duration::from_millis(50);
+
 // TODO: Add floating panel
 // TODO: Can we get away with '&str' instead of 'String' everywhere in the Tui?
-// TODO: Handle resizes
 pub trait Widget {
-    fn render(&self, terminal: &mut Terminal);
+    fn render(&self, target: &mut dyn RenderTarget);
     fn height(&self) -> usize;
     fn width(&self) -> usize;
 
     fn set_border_color(&mut self, color: Color);
+    fn set_border_style(&mut self, style: Style);
+    // The style of the content, inside the border
+    fn set_style(&mut self, style: Style);
     fn set_title(&mut self, title: Option<String>);
-
-    // TODO: Add methods for inner height and width for content rendering.
+    fn set_borders(&mut self, borders: Borders);
+    fn set_border_type(&mut self, border_type: BorderType);
+    // The space between the border and the content
+    fn set_padding(&mut self, padding: Spacing);
+    // The space around the border, nothing is drawn there
+    fn set_margin(&mut self, margin: Spacing);
+
+    /// The area where the content is drawn, inside the border and the padding.
+    fn inner(&self) -> Rectangle;
 }
 
-pub struct Terminal {
-    buffer: Vec<Cell>,
-    width: usize,
-    height: usize,
+/// The screen we draw on. Everything is rendered into an in-memory frame which is sent to the
+/// `output` in a single write per `draw`, by default stdout, but any sink works, e.g. a handle to
+/// '/dev/tty' or a `Vec<u8>` to capture the escape sequences.
+pub struct Terminal<W: Write = Stdout> {
+    frame: Frame,
+    // The last frame flushed to the terminal, used to only emit the cells that changed. An empty
+    // buffer means that we do not know what is on the screen, so everything must be repainted.
+    previous_buffer: Vec<Cell>,
+
+    output: W,
+    // Everything written to the terminal is accumulated here until it's flushed to the output
+    output_buffer: Vec<u8>,
+
+    // Absent when running headless
+    tty: Option<Tty>,
+    // Bytes read from the tty that were not decoded into events yet
+    input: Vec<u8>,
+    // When the part of a sequence left in the input arrived. Only the time it waits for the rest
+    // tells a lone escape apart from the start of a sequence, and it may span several polls.
+    partial_since: Option<Instant>,
+    mouse_capture: bool,
+    alternate_screen: bool,
+    color_support: ColorSupport,
+}
 
-    tty: std::fs::File,
+struct Tty {
+    file: std::fs::File,
     termios: Termios,
+    // The SIGWINCH handler installed before ours, restored when the terminal is dropped
+    previous_sigwinch_action: libc::sigaction,
+}
+
+pub struct TerminalOptions {
+    /// Draws on the alternate screen, so the user's scrollback is left untouched and restored when
+    /// the terminal is dropped. When disabled the last frame stays visible after the program exits.
+    pub alternate_screen: bool,
+    /// Colors the terminal can't show are replaced by the closest ones it can. It's guessed from
+    /// the environment by default.
+    pub color_support: ColorSupport,
+}
+
+impl Default for TerminalOptions {
+    fn default() -> Self {
+        TerminalOptions {
+            alternate_screen: true,
+            color_support: ColorSupport::from_env(),
+        }
+    }
+}
+
+// The state the panic hook needs to give the terminal back to the user, since it can't reach the
+// `Terminal` itself. The termios is taken by whoever restores it first, the hook or `Drop`.
+static ORIGINAL_TERMIOS: Mutex<Option<Termios>> = Mutex::new(None);
+static ALTERNATE_SCREEN: AtomicBool = AtomicBool::new(false);
+
+/// Installs a panic hook that takes the terminal out of raw mode, shows the cursor and leaves the
+/// alternate screen before printing the panic message through the previously installed hook.
+/// Otherwise the message is printed over the interface and mangled by the raw mode.
+pub fn install_panic_hook() {
+    let previous_hook = std::panic::take_hook();
+
+    std::panic::set_hook(Box::new(move |info| {
+        restore_terminal_on_panic();
+        previous_hook(info);
+    }));
+}
+
+fn restore_terminal_on_panic() {
+    let Some(termios) = ORIGINAL_TERMIOS
+        .lock()
+        .unwrap_or_else(|err| err.into_inner())
+        .take()
+    else {
+        // There is no terminal, or it was already restored
+        return;
+    };
+
+    let Ok(mut tty) = std::fs::OpenOptions::new().write(true).open("/dev/tty") else {
+        return;
+    };
+
+    // Whatever is still buffered must reach the terminal before we restore it
+    let _ = stdout().flush();
+
+    unsafe {
+        libc::tcsetattr(tty.as_raw_fd(), libc::TCSANOW, &termios);
+    }
+
+    // Disabling the mouse capture is harmless even when it was not enabled
+    let _ = write!(tty, "\x1b[?1006l\x1b[?1002l\x1b[?1000l");
+
+    if ALTERNATE_SCREEN.swap(false, Ordering::SeqCst) {
+        let _ = write!(tty, "\x1b[?1049l");
+    }
+
+    let _ = write!(tty, "\x1b[?25h");
+}
+
+// Set by the SIGWINCH handler, signal handlers can't do much more than flipping a flag safely
+static RESIZED: AtomicBool = AtomicBool::new(false);
+
+extern "C" fn handle_sigwinch(_signal: libc::c_int) {
+    RESIZED.store(true, Ordering::SeqCst);
 }
 
-impl Drop for Terminal {
+impl<W: Write> Drop for Terminal<W> {
     fn drop(&mut self) {
-        if let Err(err) = self.disable_raw_mode() {
+        // Running headless, there is nothing to restore
+        let Some(tty) = &self.tty else {
+            return;
+        };
+
+        tty.restore_sigwinch_handler();
+
+        let restored_by_panic_hook = ORIGINAL_TERMIOS
+            .lock()
+            .unwrap_or_else(|err| err.into_inner())
+            .take()
+            .is_none();
+
+        // Doing it all over again would clobber the panic message
+        if restored_by_panic_hook {
+            return;
+        }
+
+        if let Err(err) = tty.disable_raw_mode() {
             eprintln!("ERROR: Could not return the terminal to canonical mode, run 'reset' to force it back: {err}")
         };
 
-        Terminal::make_cursor_visible();
+        if self.mouse_capture {
+            // Any error here also shows up when flushing below
+            let _ = self.disable_mouse_capture();
+        }
+
+        self.leave_screen();
+
+        if let Err(err) = self.flush_output() {
+            eprintln!("ERROR: Could not restore the terminal, run 'reset' to force it back: {err}")
+        }
     }
 }
 
-impl Terminal {
-    pub fn try_new() -> std::io::Result<Terminal> {
-        let tty = std::fs::File::open("/dev/tty")?;
+impl Tty {
+    fn enable_raw_mode(&self) -> std::io::Result<()> {
+        // We keep the original Termios untouched so we can reset it's state back
+        let mut termios = self.termios;
 
-        let termios = Terminal::init_termios(&tty)?;
-        let (width, height) = Terminal::size().unwrap();
+        unsafe { libc::cfmakeraw(&mut termios) }
 
-        let terminal = Terminal {
-            buffer: vec![Cell::default(); width * height],
-            width,
-            height,
-            tty,
+        unsafe {
+            if libc::tcsetattr(self.file.as_raw_fd(), libc::TCSANOW, &termios) < 0 {
+                return Err(std::io::Error::last_os_error());
+            }
+        }
+
+        Ok(())
+    }
+
+    fn disable_raw_mode(&self) -> std::io::Result<()> {
+        unsafe {
+            if libc::tcsetattr(self.file.as_raw_fd(), libc::TCSANOW, &self.termios) < 0 {
+                return Err(std::io::Error::last_os_error());
+            };
+        }
+
+        Ok(())
+    }
+
+    fn restore_sigwinch_handler(&self) {
+        unsafe {
+            libc::sigaction(
+                libc::SIGWINCH,
+                &self.previous_sigwinch_action,
+                std::ptr::null_mut(),
+            );
+        }
+    }
+}
+
+impl Terminal<Stdout> {
+    pub fn try_new() -> std::io::Result<Terminal<Stdout>> {
+        Terminal::try_with_options(TerminalOptions::default())
+    }
+
+    pub fn try_with_options(options: TerminalOptions) -> std::io::Result<Terminal<Stdout>> {
+        Terminal::try_with_output(stdout(), options)
+    }
+}
+
+impl Terminal<Vec<u8>> {
+    /// Creates a terminal of a fixed size that is not attached to any tty, so it never touches the
+    /// user's terminal and can't read events. Useful to test what the widgets render, through
+    /// `cell` and `dump_buffer`, and what `draw` emits, through `output`.
+    pub fn headless(width: usize, height: usize) -> Terminal<Vec<u8>> {
+        Terminal {
+            frame: Frame::new(width, height),
+            previous_buffer: Vec::new(),
+            output: Vec::new(),
+            output_buffer: Vec::new(),
+            tty: None,
+            input: Vec::new(),
+            partial_since: None,
+            mouse_capture: false,
+            alternate_screen: false,
+            color_support: ColorSupport::TrueColor,
+        }
+    }
+}
+
+impl<W: Write> Terminal<W> {
+    /// Creates a terminal that sends everything it draws to `output`, the input and the terminal
+    /// size still come from '/dev/tty'.
+    pub fn try_with_output(output: W, options: TerminalOptions) -> std::io::Result<Terminal<W>> {
+        let file = std::fs::File::open("/dev/tty")?;
+
+        let termios = Self::init_termios(&file)?;
+        let (width, height) = Self::size(&file)?;
+        let previous_sigwinch_action = Self::install_sigwinch_handler()?;
+
+        let tty = Tty {
+            file,
             termios,
+            previous_sigwinch_action,
+        };
+        // There is no terminal to drop yet, so nobody else would put the handler back
+        if let Err(err) = tty.enable_raw_mode() {
+            tty.restore_sigwinch_handler();
+            return Err(err);
+        }
+
+        let mut terminal = Terminal {
+            frame: Frame::new(width, height),
+            previous_buffer: Vec::new(),
+            output,
+            output_buffer: Vec::new(),
+            tty: Some(tty),
+            input: Vec::new(),
+            partial_since: None,
+            mouse_capture: false,
+            alternate_screen: options.alternate_screen,
+            color_support: options.color_support,
         };
 
-        terminal.enable_raw_mode()?;
+        *ORIGINAL_TERMIOS
+            .lock()
+            .unwrap_or_else(|err| err.into_inner()) = Some(termios);
 
-        Terminal::make_cursor_invisible();
+        terminal.enter_screen();
+        terminal.flush_output()?;
 
         Ok(terminal)
     }
@@ -76,67 +330,152 @@ impl Terminal {
         }
     }
 
-    fn enable_raw_mode(&self) -> std::io::Result<()> {
-        // We keep the original Termios untouched so we can reset it's state back
-        let mut termios = self.termios;
-
-        unsafe { libc::cfmakeraw(&mut termios) }
-
+    fn install_sigwinch_handler() -> std::io::Result<libc::sigaction> {
         unsafe {
-            if libc::tcsetattr(self.tty.as_raw_fd(), libc::TCSANOW, &termios) < 0 {
+            let mut action: libc::sigaction = std::mem::zeroed();
+            action.sa_sigaction = handle_sigwinch as extern "C" fn(libc::c_int) as usize;
+            libc::sigemptyset(&mut action.sa_mask);
+
+            let mut previous_action: libc::sigaction = std::mem::zeroed();
+            if libc::sigaction(libc::SIGWINCH, &action, &mut previous_action) < 0 {
                 return Err(std::io::Error::last_os_error());
             }
+
+            Ok(previous_action)
+        }
+    }
+
+    /// Sends the frame to the output. When it fails we do not know how much of it reached the
+    /// terminal, so the next `draw` repaints everything.
+    pub fn draw(&mut self) -> std::io::Result<()> {
+        let result = Self::write_diff(
+            &self.previous_buffer,
+            &self.frame.buffer,
+            self.frame.width,
+            self.color_support,
+            &mut self.output_buffer,
+        )
+        .and_then(|()| self.flush_output());
+
+        if let Err(err) = result {
+            self.output_buffer.clear();
+            self.force_redraw();
+            return Err(err);
         }
 
+        // The frame we just flushed becomes the reference for the next one
+        std::mem::swap(&mut self.frame.buffer, &mut self.previous_buffer);
+        self.frame.buffer.clear();
+        self.frame
+            .buffer
+            .resize(self.frame.width * self.frame.height, Cell::default());
+        self.frame.thumbs.clear();
+
         Ok(())
     }
 
-    fn disable_raw_mode(&mut self) -> std::io::Result<()> {
-        unsafe {
-            if libc::tcsetattr(self.tty.as_raw_fd(), libc::TCSANOW, &self.termios) < 0 {
-                return Err(std::io::Error::last_os_error());
+    /// Makes the next call to `draw` clear the screen and repaint every cell, which is useful when
+    /// something else has written to the terminal behind our back.
+    pub fn force_redraw(&mut self) {
+        self.previous_buffer.clear();
+    }
+
+    /// Writes the escape sequences needed to transform the `previous` frame into the `current` one.
+    /// Only the changed cells are emitted, moving the cursor whenever the next changed cell is not
+    /// right after the last written one. An empty `previous` frame forces a full repaint.
+    fn write_diff(
+        previous: &[Cell],
+        current: &[Cell],
+        width: usize,
+        color_support: ColorSupport,
+        output: &mut impl Write,
+    ) -> std::io::Result<()> {
+        let full_redraw = previous.len() != current.len();
+
+        if full_redraw {
+            Self::clear_screen(output)?;
+        }
+
+        // The style and the cursor position are unknown until we write the first changed cell
+        let mut current_style = None;
+        let mut cursor = None;
+
+        for (i, cell) in current.iter().enumerate() {
+            if !full_redraw && previous[i] == *cell {
+                continue;
+            }
+
+            // Drawn together with the wide cluster on its left
+            if cell.is_continuation() {
+                continue;
+            }
+
+            let (x, y) = (i % width, i / width);
+
+            if cursor != Some((x, y)) {
+                // The escape sequence is 1-based
+                write!(output, "\x1b[{};{}H", y + 1, x + 1)?;
+            }
+
+            if current_style != Some(cell.style()) {
+                Style::write_transition(current_style, cell.style(), color_support, output)?;
+                current_style = Some(cell.style());
+            }
+
+            output.write_all(cell.symbol().as_bytes())?;
+
+            // After writing to the last column the cursor does not move, so we can't rely on it
+            let next_x = x + width::grapheme_width(cell.symbol()).max(1);
+            cursor = if next_x < width {
+                Some((next_x, y))
+            } else {
+                None
             };
         }
 
         Ok(())
     }
 
-    pub fn draw(&mut self) {
-        Terminal::clear_screen();
+    /// Checks if the terminal was resized since the last call, returning the new size if so.
+    ///
+    /// When it happens the buffer is reallocated and the next `draw` repaints the whole screen, so
+    /// every `Rectangle` taken from `area` before is stale and the layout must be computed again.
+    pub fn check_resize(&mut self) -> std::io::Result<Option<(usize, usize)>> {
+        let Some(tty) = &self.tty else {
+            return Ok(None);
+        };
 
-        // We always start with the Default color to ensure consistency
-        let mut current_foreground_color = Color::Default;
-        let mut current_background_color = Color::Default;
-        current_foreground_color.apply_foreground();
-        current_background_color.apply_background();
+        if !RESIZED.swap(false, Ordering::SeqCst) {
+            return Ok(None);
+        }
 
-        for line in (0..self.buffer.len()).step_by(self.width) {
-            for i in line..line + self.width {
-                let cell = self.buffer[i];
+        let (width, height) = Self::size(&tty.file)?;
+        self.resize(width, height);
 
-                if cell.foreground_color != current_foreground_color {
-                    current_foreground_color = cell.foreground_color;
-                    current_foreground_color.apply_foreground();
-                }
+        Ok(Some((width, height)))
+    }
 
-                if cell.background_color != current_background_color {
-                    current_background_color = cell.background_color;
-                    current_background_color.apply_background();
-                }
+    // Reallocates the buffer for the new size, whatever was on the screen is now meaningless
+    fn resize(&mut self, width: usize, height: usize) {
+        self.frame = Frame::new(width, height);
+        self.force_redraw();
+    }
 
-                print!("{}", cell.character)
-            }
-        }
+    /// The cell at the given position of the frame being rendered, see `Frame::cell`.
+    pub fn cell(&self, x: usize, y: usize) -> &Cell {
+        self.frame.cell(x, y)
+    }
 
-        stdout().flush().unwrap();
-        self.buffer.fill(Cell::default())
+    /// Dumps the characters of the frame being rendered, see `Frame::dump_buffer`.
+    pub fn dump_buffer(&self) -> String {
+        self.frame.dump_buffer()
     }
 
     pub fn area(&self) -> Rectangle {
-        Rectangle::new(None, 0, 0, self.width, self.height)
+        Rectangle::new(None, 0, 0, self.frame.width, self.frame.height)
     }
 
-    fn size() -> std::io::Result<(usize, usize)> {
+    fn size(tty: &std::fs::File) -> std::io::Result<(usize, usize)> {
         #[repr(C)]
         struct TermSize {
             row: libc::c_ushort,
@@ -147,7 +486,7 @@ impl Terminal {
 
         unsafe {
             let mut size: TermSize = std::mem::zeroed();
-            if libc::ioctl(libc::STDOUT_FILENO, libc::TIOCGWINSZ, &mut size) < 0 {
+            if libc::ioctl(tty.as_raw_fd(), libc::TIOCGWINSZ, &mut size) < 0 {
                 return Err(std::io::Error::last_os_error());
             }
 
@@ -155,6 +494,288 @@ impl Terminal {
         }
     }
 
+    fn clear_screen(output: &mut impl Write) -> std::io::Result<()> {
+        write!(output, "\x1b[2J")
+    }
+
+    /// Asks the terminal to report mouse clicks, drags and scrolls, which then show up as
+    /// `Event::Mouse` events.
+    pub fn enable_mouse_capture(&mut self) -> std::io::Result<()> {
+        // 1000 reports presses and releases, 1002 adds motion while a button is held and 1006
+        // switches to the SGR encoding, which has no limit on the coordinates
+        write!(self.output_buffer, "\x1b[?1000h\x1b[?1002h\x1b[?1006h")?;
+        self.mouse_capture = true;
+
+        self.flush_output()
+    }
+
+    pub fn disable_mouse_capture(&mut self) -> std::io::Result<()> {
+        write!(self.output_buffer, "\x1b[?1006l\x1b[?1002l\x1b[?1000l")?;
+        self.mouse_capture = false;
+
+        self.flush_output()
+    }
+
+    /// Gives access to the output, e.g. to inspect what was written to an in-memory sink.
+    pub fn output(&self) -> &W {
+        &self.output
+    }
+
+    /// Sends everything written so far to the output in a single write.
+    fn flush_output(&mut self) -> std::io::Result<()> {
+        self.output.write_all(&self.output_buffer)?;
+        self.output_buffer.clear();
+
+        self.output.flush()
+    }
+
+    // Writing to a `Vec` can't fail, so the functions below do not bother returning errors
+
+    fn enter_screen(&mut self) {
+        if self.alternate_screen {
+            self.enter_alternate_screen();
+        }
+
+        self.make_cursor_invisible();
+    }
+
+    fn leave_screen(&mut self) {
+        if self.alternate_screen {
+            self.leave_alternate_screen();
+        } else {
+            // Leave the cursor below the last frame, so the shell prompt does not overwrite it
+            write!(self.output_buffer, "\x1b[{};1H\r\n", self.frame.height).unwrap();
+        }
+
+        self.make_cursor_visible();
+    }
+
+    fn enter_alternate_screen(&mut self) {
+        self.output_buffer.extend_from_slice(b"\x1b[?1049h");
+        ALTERNATE_SCREEN.store(true, Ordering::SeqCst);
+    }
+
+    fn leave_alternate_screen(&mut self) {
+        self.output_buffer.extend_from_slice(b"\x1b[?1049l");
+        ALTERNATE_SCREEN.store(false, Ordering::SeqCst);
+    }
+
+    fn make_cursor_invisible(&mut self) {
+        self.output_buffer.extend_from_slice(b"\x1b[?25l");
+    }
+
+    fn make_cursor_visible(&mut self) {
+        self.output_buffer.extend_from_slice(b"\x1b[?25h");
+    }
+
+    /// Blocks until the user presses a key or the terminal is resized.
+    pub fn read_event(&mut self) -> std::io::Result<Event> {
+        loop {
+            if let Some(event) = self.next_event(None)? {
+                return Ok(event);
+            }
+        }
+    }
+
+    /// Waits up to `timeout` for the user to press a key or for the terminal to be resized,
+    /// returning `None` if nothing happened in the meantime. A zero timeout never blocks, which
+    /// allows an application loop to redraw at a fixed rate while still reacting to the input.
+    pub fn poll_event(&mut self, timeout: Duration) -> std::io::Result<Option<Event>> {
+        self.next_event(Some(timeout))
+    }
+
+    fn next_event(&mut self, timeout: Option<Duration>) -> std::io::Result<Option<Event>> {
+        let deadline = timeout.map(|timeout| Instant::now() + timeout);
+
+        loop {
+            if let Some((width, height)) = self.check_resize()? {
+                return Ok(Some(Event::Resize(width, height)));
+            }
+
+            if let Some(event) = self.parse_input(false) {
+                self.partial_since = None;
+                return Ok(Some(event));
+            }
+
+            let now = Instant::now();
+
+            // With part of a sequence in hands we only wait a little for the rest of it
+            let escape_deadline = if self.input.is_empty() {
+                self.partial_since = None;
+                None
+            } else {
+                let since = *self.partial_since.get_or_insert(now);
+                Some(since + ESCAPE_TIMEOUT)
+            };
+
+            if escape_deadline.is_some_and(|escape_deadline| now >= escape_deadline) {
+                self.partial_since = None;
+                if let Some(event) = self.parse_input(true) {
+                    return Ok(Some(event));
+                }
+                continue;
+            }
+
+            let wait = [deadline, escape_deadline]
+                .into_iter()
+                .flatten()
+                .min()
+                .map(|until| until.saturating_duration_since(now));
+
+            match self.wait_for_input(wait) {
+                Ok(true) => self.read_input()?,
+                // The deadline expired, a partial sequence is kept around for the next call
+                Ok(false) if deadline.is_some_and(|deadline| Instant::now() >= deadline) => {
+                    return Ok(None)
+                }
+                // Waited long enough for the rest of the sequence, on to the next loop
+                Ok(false) => continue,
+                // Interrupted by a signal, most likely SIGWINCH, which we check on the next loop
+                Err(err) if err.kind() == std::io::ErrorKind::Interrupted => continue,
+                Err(err) => return Err(err),
+            }
+        }
+    }
+
+    /// Decodes the next event from the bytes already read, if there is a complete one.
+    fn parse_input(&mut self, timed_out: bool) -> Option<Event> {
+        loop {
+            match event::parse_event(&self.input, timed_out) {
+                Parsed::Event(event, length) => {
+                    self.input.drain(..length);
+                    return Some(event);
+                }
+                Parsed::Invalid(length) => {
+                    self.input.drain(..length);
+                }
+                // The rest of the sequence is never coming, drop it byte by byte until we can make
+                // sense of the input again
+                Parsed::Incomplete if timed_out && !self.input.is_empty() => {
+                    self.input.remove(0);
+                }
+                Parsed::Incomplete => return None,
+            }
+        }
+    }
+
+    /// Waits until the tty has something to be read, returning false if the timeout expired first.
+    /// No timeout means waiting forever.
+    fn wait_for_input(&self, timeout: Option<Duration>) -> std::io::Result<bool> {
+        let Some(tty) = &self.tty else {
+            return Err(Self::headless_input_error());
+        };
+
+        let mut fd = libc::pollfd {
+            fd: tty.file.as_raw_fd(),
+            events: libc::POLLIN,
+            revents: 0,
+        };
+
+        let timeout = match timeout {
+            Some(timeout) => timeout.as_millis().min(libc::c_int::MAX as u128) as libc::c_int,
+            None => -1,
+        };
+
+        match unsafe { libc::poll(&mut fd, 1, timeout) } {
+            result if result < 0 => Err(std::io::Error::last_os_error()),
+            0 => Ok(false),
+            _ if fd.revents & libc::POLLIN != 0 => Ok(true),
+            // Hung up or broken, it's never going to have anything to read
+            _ => Err(Self::closed_input_error()),
+        }
+    }
+
+    fn read_input(&mut self) -> std::io::Result<()> {
+        let Some(tty) = &self.tty else {
+            return Err(Self::headless_input_error());
+        };
+
+        let mut bytes = [0; 1024];
+
+        match (&tty.file).read(&mut bytes) {
+            Ok(0) => Err(Self::closed_input_error()),
+            Ok(length) => {
+                self.input.extend_from_slice(&bytes[..length]);
+                Ok(())
+            }
+            Err(err) if err.kind() == std::io::ErrorKind::Interrupted => Ok(()),
+            Err(err) => Err(err),
+        }
+    }
+
+    fn closed_input_error() -> std::io::Error {
+        std::io::Error::new(std::io::ErrorKind::UnexpectedEof, "the terminal was closed")
+    }
+
+    fn headless_input_error() -> std::io::Error {
+        std::io::Error::new(
+            std::io::ErrorKind::Unsupported,
+            "a headless terminal has no input",
+        )
+    }
+}
+
+/// The cells widgets are rendered into, what the next `draw` of the `Terminal` is going to show.
+pub struct Frame {
+    buffer: Vec<Cell>,
+    width: usize,
+    height: usize,
+    // Where the thumbs of the scrollbars are, the merged borders drawn later leave them alone
+    thumbs: Vec<usize>,
+}
+
+/// Something widgets can be rendered into, e.g. a `Terminal`, whatever its output is.
+pub trait RenderTarget {
+    fn frame(&mut self) -> &mut Frame;
+}
+
+impl RenderTarget for Frame {
+    fn frame(&mut self) -> &mut Frame {
+        self
+    }
+}
+
+impl<W: Write> RenderTarget for Terminal<W> {
+    fn frame(&mut self) -> &mut Frame {
+        &mut self.frame
+    }
+}
+
+impl Frame {
+    /// An empty frame, to render widgets without a terminal.
+    pub fn new(width: usize, height: usize) -> Frame {
+        Frame {
+            buffer: vec![Cell::default(); width * height],
+            width,
+            height,
+            thumbs: Vec::new(),
+        }
+    }
+
+    /// The cell at the given position. Panics if the position is outside of the frame.
+    pub fn cell(&self, x: usize, y: usize) -> &Cell {
+        assert!(x < self.width && y < self.height);
+
+        &self.buffer[self.position_to_buffer_index(x, y)]
+    }
+
+    /// Dumps the characters as plain text, one line per row.
+    pub fn dump_buffer(&self) -> String {
+        // There are no rows without columns
+        if self.width == 0 {
+            return String::new();
+        }
+
+        let mut text = String::with_capacity((self.width + 1) * self.height);
+
+        for line in self.buffer.chunks(self.width) {
+            text.extend(line.iter().map(|cell| cell.symbol()));
+            text.push('\n');
+        }
+
+        text
+    }
+
     #[inline(always)]
     fn position_to_buffer_index(&self, x: usize, y: usize) -> usize {
         debug_assert!(x <= self.width);
@@ -163,20 +784,35 @@ impl Terminal {
         y * self.width + x
     }
 
-    fn clear_screen() {
-        print!("\x1b[2J");
-    }
+    /// Draws the grapheme cluster in the cell, taking the one on its right as well if it's wide.
+    /// Wide clusters partially overwritten are replaced by spaces, since the terminal can't draw
+    /// half of them, and a wide one that would not fit in the last column becomes a space too.
+    fn put_symbol(&mut self, buffer_index: usize, symbol: &str) {
+        let x = buffer_index % self.width;
 
-    fn make_cursor_invisible() {
-        print!("\x1b[?25l");
-    }
+        let (symbol, width) = match width::grapheme_width(symbol) {
+            2 if x + 1 == self.width => (" ", 1),
+            0 => (" ", 1),
+            width => (symbol, width),
+        };
 
-    fn make_cursor_visible() {
-        print!("\x1b[?25h");
-    }
+        if self.buffer[buffer_index].is_continuation() {
+            self.buffer[buffer_index - 1].set_symbol(" ");
+        }
 
-    pub fn tty(&self) -> std::io::Result<std::io::Bytes<std::fs::File>> {
-        self.tty.try_clone().map(|file| file.bytes())
+        self.buffer[buffer_index].set_symbol(symbol);
+
+        // Whatever follows the cells we took can't be a continuation anymore
+        let next = buffer_index + width;
+        if x + width < self.width && self.buffer[next].is_continuation() {
+            self.buffer[next].set_symbol(" ");
+        }
+
+        if width == 2 {
+            let style = self.buffer[buffer_index].style();
+            self.buffer[buffer_index + 1].set_continuation();
+            self.buffer[buffer_index + 1].set_style(style);
+        }
     }
 }
 
@@ -186,7 +822,14 @@ pub struct Rectangle {
     y: usize,
     width: usize,
     height: usize,
-    border_color: Color,
+    border_style: Style,
+    style: Style,
+    borders: Borders,
+    border_type: BorderType,
+    padding: Spacing,
+    margin: Spacing,
+    // Whether the parts of this rectangle share their walls, joining the borders where they meet
+    merged_borders: bool,
 }
 
 impl Rectangle {
@@ -197,10 +840,76 @@ impl Rectangle {
             y,
             width,
             height,
-            border_color: Color::Default,
+            border_style: Style::default(),
+            style: Style::default(),
+            borders: Borders::ALL,
+            border_type: BorderType::Plain,
+            padding: Spacing::ZERO,
+            margin: Spacing::ZERO,
+            merged_borders: false,
         }
     }
 
+    /// Splits the rectangle in one part for each constraint along the direction, see
+    /// `Constraint::resolve` for how the space is split. The parts always cover the whole
+    /// rectangle, so the space the constraints leave unused goes to the last part.
+    ///
+    /// ```text
+    /// Horizontal:              Vertical:
+    /// +-----++----------+      +----------------+
+    /// |     ||          |      |                |
+    /// |     ||          |      +----------------+
+    /// |     ||          |      +----------------+
+    /// |     ||          |      |                |
+    /// +-----++----------+      +----------------+
+    /// ```
+    ///
+    /// With `set_merged_borders` the parts share the walls between them instead.
+    pub fn split(self, direction: Direction, constraints: &[Constraint]) -> Vec<Rectangle> {
+        let available = match direction {
+            Direction::Horizontal => self.width,
+            Direction::Vertical => self.height,
+        };
+
+        layout::tracks(constraints, available, self.merged_borders)
+            .into_iter()
+            .map(|(start, size)| {
+                let (x, y, width, height) = match direction {
+                    Direction::Horizontal => (self.x + start, self.y, size, self.height),
+                    Direction::Vertical => (self.x, self.y + start, self.width, size),
+                };
+
+                Rectangle {
+                    border_style: self.border_style,
+                    style: self.style,
+                    border_type: self.border_type,
+                    merged_borders: self.merged_borders,
+                    ..Rectangle::new(None, x, y, width, height)
+                }
+            })
+            .collect()
+    }
+
+    /// Divides the rectangle in rows and columns, one for each constraint, see `Grid`.
+    pub fn grid(self, rows: &[Constraint], columns: &[Constraint]) -> Grid {
+        Grid::new(self, rows, columns)
+    }
+
+    /// Makes the parts this rectangle is split in, by `split` or `grid`, share the walls between
+    /// them, and join their borders with the ones already drawn where they meet, e.g. `├` where a
+    /// wall meets another one. The parts inherit it, so nested splits are joined as well.
+    ///
+    /// ```text
+    /// +-----+----------+
+    /// |     |          |
+    /// |     +----------+
+    /// |     |          |
+    /// +-----+----------+
+    /// ```
+    pub fn set_merged_borders(&mut self, merged_borders: bool) {
+        self.merged_borders = merged_borders
+    }
+
     pub fn split_horizontally(self) -> (Rectangle, Rectangle) {
         self.split_horizontally_at(0.5)
     }
@@ -216,26 +925,8 @@ impl Rectangle {
         assert!(percentage > 0.0 && percentage < 1.0);
 
         let left_width = (self.width as f32 * percentage) as usize;
-        let right_width = self.width - left_width;
-
-        let left = Rectangle {
-            title: None,
-            x: self.x,
-            y: self.y,
-            width: left_width,
-            height: self.height,
-            border_color: self.border_color,
-        };
-        let right = Rectangle {
-            title: None,
-            x: self.x + left_width,
-            y: self.y,
-            width: right_width,
-            height: self.height,
-            border_color: self.border_color,
-        };
 
-        (left, right)
+        self.split_in_two(Direction::Horizontal, left_width)
     }
 
     pub fn split_vertically(self) -> (Rectangle, Rectangle) {
@@ -253,26 +944,19 @@ impl Rectangle {
         assert!(percentage > 0.0 && percentage < 1.0);
 
         let top_height = (self.height as f32 * percentage) as usize;
-        let bottom_height = self.height - top_height;
-
-        let top = Rectangle {
-            title: None,
-            x: self.x,
-            y: self.y,
-            width: self.width,
-            height: top_height,
-            border_color: self.border_color,
-        };
-        let bottom = Rectangle {
-            title: None,
-            x: self.x,
-            y: self.y + top_height,
-            width: self.width,
-            height: bottom_height,
-            border_color: self.border_color,
-        };
 
-        (top, bottom)
+        self.split_in_two(Direction::Vertical, top_height)
+    }
+
+    fn split_in_two(self, direction: Direction, first_size: usize) -> (Rectangle, Rectangle) {
+        let mut parts = self.split(
+            direction,
+            &[Constraint::Length(first_size), Constraint::Fill(1)],
+        );
+        let second = parts.pop().expect("there are two constraints");
+        let first = parts.pop().expect("there are two constraints");
+
+        (first, second)
     }
 
     pub fn text(
@@ -302,59 +986,197 @@ impl Rectangle {
         Table::new(items, vertical_alignment, horizontal_alignment, self)
     }
 
-    #[inline(always)]
-    fn position_to_buffer_index(&self, terminal: &Terminal, x: usize, y: usize) -> usize {
-        debug_assert!(x <= self.width);
-        debug_assert!(y <= self.height);
+    /// Checks if the terminal position, e.g. the one from a `MouseEvent`, is inside the rectangle.
+    pub fn contains(&self, column: usize, row: usize) -> bool {
+        (self.x..self.x + self.width).contains(&column)
+            && (self.y..self.y + self.height).contains(&row)
+    }
+
+    // The rectangle without the `spacing` around it, it has no border or spacing of its own
+    fn inset(&self, spacing: Spacing) -> Rectangle {
+        let left = usize::min(spacing.left, self.width);
+        let top = usize::min(spacing.top, self.height);
+
+        Rectangle {
+            border_style: self.border_style,
+            style: self.style,
+            borders: Borders::NONE,
+            ..Rectangle::new(
+                None,
+                self.x + left,
+                self.y + top,
+                (self.width - left).saturating_sub(spacing.right),
+                (self.height - top).saturating_sub(spacing.bottom),
+            )
+        }
+    }
+
+    fn render_title(&self, frame: &mut Frame) {
+        // The title goes over the top border, there is nowhere to draw it without one
+        let Some(title) = &self.title else {
+            return;
+        };
+        if !self.borders.contains(Borders::TOP) {
+            return;
+        }
+
+        let area = self.border_box();
+        // After the top left corner and one line, stopping before the top right corner
+        let x = 1 + self.borders.width(Borders::LEFT);
+        let max_width = area
+            .width
+            .saturating_sub(x + self.borders.width(Borders::RIGHT));
+        area.put_str(frame, x, 0, title, max_width);
+    }
+
+    // Draws a line of the border, joining it to the borders already there if they are merged
+    fn put_border_symbol(&self, frame: &mut Frame, buffer_index: usize, symbol: &str) {
+        let symbol = if !self.merged_borders {
+            symbol
+        } else if frame.thumbs.contains(&buffer_index) {
+            // The scrollbar of a neighbour is drawn over the wall we share with it on purpose
+            return;
+        } else {
+            border::merge(
+                frame.buffer[buffer_index].symbol(),
+                symbol,
+                self.border_type,
+            )
+        };
+
+        frame.buffer[buffer_index].set_style(self.border_style);
+        frame.put_symbol(buffer_index, symbol);
+    }
+
+    // The rectangle the border is drawn around, inside the margin
+    fn border_box(&self) -> Rectangle {
+        self.inset(self.margin)
+    }
+
+    /// Draws the text in a single line starting at the position, relative to the rectangle, and
+    /// taking at most `max_width` columns. Returns how many columns it took.
+    fn put_str(
+        &self,
+        frame: &mut Frame,
+        x: usize,
+        y: usize,
+        text: &str,
+        max_width: usize,
+    ) -> usize {
+        let mut used_width = 0;
+
+        for grapheme in Graphemes::new(text) {
+            let grapheme_width = width::grapheme_width(grapheme);
+
+            // Line breaks and stray combining marks, there is nothing to draw
+            if grapheme_width == 0 {
+                continue;
+            }
+
+            if used_width + grapheme_width > max_width {
+                break;
+            }
+
+            let buffer_index = self.position_to_buffer_index(frame, x + used_width, y);
+            frame.put_symbol(buffer_index, grapheme);
+            used_width += grapheme_width;
+        }
+
+        used_width
+    }
+
+    /// Draws a scrollbar over the right border, or the last column of the content if there is no
+    /// border, showing which part of the content is in view, where `visible` out of `total` rows
+    /// are shown starting at `offset`. The scrollbar spans the content from its `top` line to the
+    /// bottom. Nothing is drawn if everything fits.
+    fn render_scrollbar(
+        &self,
+        frame: &mut Frame,
+        top: usize,
+        offset: usize,
+        visible: usize,
+        total: usize,
+    ) {
+        let inner = self.inner();
+        let track_height = inner.height.saturating_sub(top);
+
+        if total <= visible || visible == 0 || track_height == 0 || inner.width == 0 {
+            return;
+        }
+
+        let border_box = self.border_box();
+        let x = if self.borders.contains(Borders::RIGHT) {
+            border_box.x + border_box.width - 1
+        } else {
+            inner.x + inner.width - 1
+        };
+
+        // The thumb is proportional to the part in view, but never vanishes
+        let thumb_height = usize::max(1, track_height * visible / total);
+        // Offsets past the last page are kept at the bottom
+        let thumb_position = usize::min(
+            offset * (track_height - thumb_height) / (total - visible),
+            track_height - thumb_height,
+        );
+
+        for y in thumb_position..thumb_position + thumb_height {
+            let buffer_index = frame.position_to_buffer_index(x, inner.y + top + y);
+            frame.put_symbol(buffer_index, self.border_type.symbols().thumb);
+            frame.thumbs.push(buffer_index);
+        }
+    }
+
+    #[inline(always)]
+    fn position_to_buffer_index(&self, frame: &Frame, x: usize, y: usize) -> usize {
+        debug_assert!(x <= self.width);
+        debug_assert!(y <= self.height);
 
-        terminal.position_to_buffer_index(self.x + x, self.y + y)
+        frame.position_to_buffer_index(self.x + x, self.y + y)
     }
 }
 
 impl Widget for Rectangle {
-    fn render(&self, terminal: &mut Terminal) {
+    fn render(&self, target: &mut dyn RenderTarget) {
+        let frame = target.frame();
+
+        let area = self.border_box();
+        let symbols = self.border_type.symbols();
+
+        let top = self.borders.contains(Borders::TOP);
+        let right = self.borders.contains(Borders::RIGHT);
+        let bottom = self.borders.contains(Borders::BOTTOM);
+        let left = self.borders.contains(Borders::LEFT);
+
         // We iterate in this order to help with cache locality
-        for y in 0..self.height {
-            for x in 0..self.width {
-                let buffer_index = self.position_to_buffer_index(terminal, x, y);
-
-                if y == 0 {
-                    if x == 0 {
-                        terminal.buffer[buffer_index].character = '┌';
-                        terminal.buffer[buffer_index].foreground_color = self.border_color;
-                    } else if x == self.width - 1 {
-                        terminal.buffer[buffer_index].character = '┐';
-                        terminal.buffer[buffer_index].foreground_color = self.border_color;
-                    } else {
-                        terminal.buffer[buffer_index].character = '─';
-                        terminal.buffer[buffer_index].foreground_color = self.border_color;
+        for y in 0..area.height {
+            for x in 0..area.width {
+                let buffer_index = area.position_to_buffer_index(frame, x, y);
+
+                let on_top = top && y == 0;
+                let on_bottom = bottom && y == area.height - 1;
+                let on_left = left && x == 0;
+                let on_right = right && x == area.width - 1;
+
+                let symbol = match (on_top, on_bottom, on_left, on_right) {
+                    (true, _, true, _) => symbols.top_left,
+                    (true, _, _, true) => symbols.top_right,
+                    (_, true, true, _) => symbols.bottom_left,
+                    (_, true, _, true) => symbols.bottom_right,
+                    (true, _, _, _) | (_, true, _, _) => symbols.horizontal,
+                    (_, _, true, _) | (_, _, _, true) => symbols.vertical,
+                    _ => {
+                        if self.style != Style::default() {
+                            frame.buffer[buffer_index].set_style(self.style);
+                        }
+                        continue;
                     }
-                } else if y == self.height - 1 {
-                    if x == 0 {
-                        terminal.buffer[buffer_index].character = '└';
-                        terminal.buffer[buffer_index].foreground_color = self.border_color;
-                    } else if x == self.width - 1 {
-                        terminal.buffer[buffer_index].character = '┘';
-                        terminal.buffer[buffer_index].foreground_color = self.border_color;
-                    } else {
-                        terminal.buffer[buffer_index].character = '─';
-                        terminal.buffer[buffer_index].foreground_color = self.border_color;
-                    }
-                } else if x == 0 || x == self.width - 1 {
-                    terminal.buffer[buffer_index].character = '│';
-                    terminal.buffer[buffer_index].foreground_color = self.border_color;
-                } else {
-                    continue;
-                }
-            }
-        }
+                };
 
-        if let Some(title) = &self.title {
-            for (x, c) in title.chars().enumerate() {
-                let buffer_index = self.position_to_buffer_index(terminal, x + 2, 0);
-                terminal.buffer[buffer_index].character = c
+                self.put_border_symbol(frame, buffer_index, symbol);
             }
         }
+
+        self.render_title(frame);
     }
 
     fn height(&self) -> usize {
@@ -366,28 +1188,71 @@ impl Widget for Rectangle {
     }
 
     fn set_border_color(&mut self, color: Color) {
-        self.border_color = color
+        self.border_style.foreground_color = color
+    }
+
+    fn set_border_style(&mut self, style: Style) {
+        self.border_style = style
+    }
+
+    fn set_style(&mut self, style: Style) {
+        self.style = style
     }
 
     fn set_title(&mut self, title: Option<String>) {
         self.title = title;
     }
+
+    fn set_borders(&mut self, borders: Borders) {
+        self.borders = borders
+    }
+
+    fn set_border_type(&mut self, border_type: BorderType) {
+        self.border_type = border_type
+    }
+
+    fn set_padding(&mut self, padding: Spacing) {
+        self.padding = padding
+    }
+
+    fn set_margin(&mut self, margin: Spacing) {
+        self.margin = margin
+    }
+
+    fn inner(&self) -> Rectangle {
+        let borders = Spacing {
+            top: self.borders.width(Borders::TOP),
+            right: self.borders.width(Borders::RIGHT),
+            bottom: self.borders.width(Borders::BOTTOM),
+            left: self.borders.width(Borders::LEFT),
+        };
+
+        self.border_box().inset(borders).inset(self.padding)
+    }
 }
 
 pub struct Text {
-    text: Vec<char>,
+    text: String,
     vertical_alignment: VerticalAlignment,
     horizontal_alignment: HorizontalAlignment,
     area: Rectangle,
+    wrap_mode: WrapMode,
     lines_count: usize,
+    // The index of the first wrapped line shown
+    scroll_offset: usize,
+    // Keeps the last lines in view as the text grows, like 'tail -f'
+    follow_tail: bool,
+    scrollbar: bool,
 }
 
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
 pub enum HorizontalAlignment {
     Left,
     Right,
     Center,
 }
 
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
 pub enum VerticalAlignment {
     Top,
     Bottom,
@@ -401,64 +1266,147 @@ impl Text {
         horizontal_alignment: HorizontalAlignment,
         area: Rectangle,
     ) -> Text {
-        let text: Vec<char> = text.chars().collect();
-        let lines_count = HardwrappingText::new(&text, area.width() - 2)
-            .into_iter()
-            .count();
+        let text = wrap::expand_tabs(text);
+        let wrap_mode = WrapMode::Hard;
+        let lines_count = WrappedLines::new(&text, area.inner().width, wrap_mode).count();
 
         Text {
             text,
             vertical_alignment,
             horizontal_alignment,
             area,
+            wrap_mode,
             lines_count,
+            scroll_offset: 0,
+            follow_tail: false,
+            scrollbar: false,
         }
     }
 
+    /// Tabs are expanded to spaces, up to the next tab stop every 4 columns.
     pub fn change_text(&mut self, new_text: Option<String>) {
         if let Some(text) = new_text {
-            self.text = text.chars().collect();
+            self.text = wrap::expand_tabs(text);
         } else {
             self.text.clear();
         }
 
-        self.lines_count = HardwrappingText::new(&self.text, self.area.width() - 2)
-            .into_iter()
-            .count();
+        self.update_lines_count();
+    }
+
+    /// Lines are hard wrapped by default.
+    pub fn set_wrap_mode(&mut self, wrap_mode: WrapMode) {
+        self.wrap_mode = wrap_mode;
+        self.update_lines_count();
+    }
+
+    /// The index of the first line in view, counting the wrapped lines.
+    pub fn scroll_offset(&self) -> usize {
+        if self.follow_tail {
+            self.max_scroll_offset()
+        } else {
+            self.scroll_offset
+        }
+    }
+
+    /// Scrolling up stops following the tail, see `set_follow_tail`.
+    pub fn scroll_up(&mut self, lines: usize) {
+        self.scroll_offset = self.scroll_offset().saturating_sub(lines);
+        self.follow_tail = false;
+    }
+
+    pub fn scroll_down(&mut self, lines: usize) {
+        self.scroll_offset = usize::min(self.scroll_offset() + lines, self.max_scroll_offset());
+    }
+
+    pub fn page_up(&mut self) {
+        self.scroll_up(self.page_height())
+    }
+
+    pub fn page_down(&mut self) {
+        self.scroll_down(self.page_height())
+    }
+
+    pub fn scroll_to_top(&mut self) {
+        self.scroll_up(self.scroll_offset())
+    }
+
+    pub fn scroll_to_bottom(&mut self) {
+        self.scroll_offset = self.max_scroll_offset();
+    }
+
+    /// Keeps the last lines in view whenever the text changes, which is what we want for logs.
+    /// Scrolling up stops it, so the user can read the older lines.
+    pub fn set_follow_tail(&mut self, follow_tail: bool) {
+        // Stays where the tail was, before the flag stops tracking it
+        self.scroll_offset = self.scroll_offset();
+        self.follow_tail = follow_tail;
+    }
+
+    /// Shows the scroll position on the right border when the text does not fit.
+    pub fn set_scrollbar(&mut self, scrollbar: bool) {
+        self.scrollbar = scrollbar
+    }
+
+    // How many lines fit inside the border
+    fn page_height(&self) -> usize {
+        self.area.inner().height
+    }
+
+    fn max_scroll_offset(&self) -> usize {
+        self.lines_count.saturating_sub(self.page_height())
+    }
+
+    fn update_lines_count(&mut self) {
+        self.lines_count = self.lines().count();
+        self.scroll_offset = usize::min(self.scroll_offset, self.max_scroll_offset());
+    }
+
+    fn lines(&self) -> WrappedLines<'_> {
+        WrappedLines::new(&self.text, self.area.inner().width, self.wrap_mode)
     }
 }
+
 impl Widget for Text {
-    fn render(&self, terminal: &mut Terminal) {
-        self.area.render(terminal);
+    fn render(&self, target: &mut dyn RenderTarget) {
+        let frame = target.frame();
+
+        self.area.render(frame);
+
+        let inner = self.inner();
+        let visible_lines = usize::min(self.lines_count, self.page_height());
 
         let y = match self.vertical_alignment {
-            VerticalAlignment::Top => 1, // 1 for the border
-            VerticalAlignment::Bottom => self.height() - 1 - 1 - self.lines_count, // -1 for the border
-            VerticalAlignment::Center => (self.height() - self.lines_count) / 2,
+            VerticalAlignment::Top => 0,
+            VerticalAlignment::Bottom => inner.height - visible_lines,
+            VerticalAlignment::Center => (inner.height - visible_lines) / 2,
         };
 
-        let hardwrapped_lines = HardwrappingText::new(&self.text, self.width() - 2);
-        for (line_index, line) in hardwrapped_lines
-            .into_iter()
-            // FIXME: Deal with scrolling
-            .take(self.height() - 2)
+        for (line_index, line) in self
+            .lines()
+            .skip(self.scroll_offset())
+            .take(visible_lines)
             .enumerate()
         {
+            let line_width = width::str_width(&line);
+
             let x = match self.horizontal_alignment {
-                HorizontalAlignment::Left => 1, // 1 for the border
-                HorizontalAlignment::Right => {
-                    self.width() - line.len() - 1 // -1 for the border
-                }
-                HorizontalAlignment::Center => (self.width() - line.len()) / 2,
+                HorizontalAlignment::Left => 0,
+                HorizontalAlignment::Right => inner.width - line_width,
+                HorizontalAlignment::Center => (inner.width - line_width) / 2,
             };
 
-            for (row_index, c) in line.iter().enumerate() {
-                let buffer_index =
-                    self.area
-                        .position_to_buffer_index(terminal, x + row_index, y + line_index);
+            inner.put_str(frame, x, y + line_index, &line, inner.width - x);
+        }
 
-                terminal.buffer[buffer_index].character = *c;
-            }
+        if self.scrollbar {
+            self.area.render_scrollbar(
+                frame,
+                0,
+                self.scroll_offset(),
+                self.page_height(),
+                self.lines_count,
+            );
         }
     }
 
@@ -474,9 +1422,40 @@ impl Widget for Text {
         self.area.set_border_color(color)
     }
 
+    fn set_border_style(&mut self, style: Style) {
+        self.area.set_border_style(style)
+    }
+
+    fn set_style(&mut self, style: Style) {
+        self.area.set_style(style)
+    }
+
     fn set_title(&mut self, title: Option<String>) {
         self.area.set_title(title);
     }
+
+    fn set_borders(&mut self, borders: Borders) {
+        self.area.set_borders(borders);
+        self.update_lines_count();
+    }
+
+    fn set_border_type(&mut self, border_type: BorderType) {
+        self.area.set_border_type(border_type)
+    }
+
+    fn set_padding(&mut self, padding: Spacing) {
+        self.area.set_padding(padding);
+        self.update_lines_count();
+    }
+
+    fn set_margin(&mut self, margin: Spacing) {
+        self.area.set_margin(margin);
+        self.update_lines_count();
+    }
+
+    fn inner(&self) -> Rectangle {
+        self.area.inner()
+    }
 }
 
 pub struct ItemList {
@@ -484,7 +1463,8 @@ pub struct ItemList {
     vertical_alignment: VerticalAlignment,
     horizontal_alignment: HorizontalAlignment,
     area: Rectangle,
-    selected_row: Option<usize>,
+    selection: Selection,
+    scrollbar: bool,
 }
 
 impl ItemList {
@@ -494,67 +1474,168 @@ impl ItemList {
         horizontal_alignment: HorizontalAlignment,
         area: Rectangle,
     ) -> ItemList {
-        assert!(items.len() <= area.height - 2); // -2 for the border
-        assert!(items.iter().map(|item| item.len()).max() < Some(area.width - 2)); // -2 for the border
-
         ItemList {
             items,
             vertical_alignment,
             horizontal_alignment,
             area,
-            selected_row: None,
+            selection: Selection::new(),
+            scrollbar: false,
         }
     }
 
+    /// Selects the item, scrolling the list so it's in view.
     pub fn set_selected(&mut self, item_index: Option<usize>) {
-        self.selected_row = item_index
+        self.selection.select(item_index, self.items.len());
+        self.scroll_to_selected();
+    }
+
+    pub fn selected(&self) -> Option<usize> {
+        self.selection.selected
+    }
+
+    /// Selects the item below the selected one, or the first one if nothing is selected.
+    pub fn select_next(&mut self) {
+        self.selection.select_next(self.items.len());
+        self.scroll_to_selected();
+    }
+
+    /// Selects the item above the selected one, or the last one if nothing is selected.
+    pub fn select_previous(&mut self) {
+        self.selection.select_previous(self.items.len());
+        self.scroll_to_selected();
+    }
+
+    /// Moves the selection a whole page up, stopping at the first item.
+    pub fn page_up(&mut self) {
+        self.selection.page_up(self.page_height(), self.items.len());
+        self.scroll_to_selected();
+    }
+
+    /// Moves the selection a whole page down, stopping at the last item.
+    pub fn page_down(&mut self) {
+        self.selection
+            .page_down(self.page_height(), self.items.len());
+        self.scroll_to_selected();
+    }
+
+    pub fn select_first(&mut self) {
+        self.set_selected(Some(0));
+    }
+
+    pub fn select_last(&mut self) {
+        self.set_selected(Some(self.items.len().saturating_sub(1)));
+    }
+
+    /// Makes `select_next` on the last item select the first one, and `select_previous` on the
+    /// first item select the last one. Disabled by default.
+    pub fn set_wrap_around(&mut self, wrap_around: bool) {
+        self.selection.wrap_around = wrap_around
+    }
+
+    /// Shows the scroll position on the right border when the items do not fit.
+    pub fn set_scrollbar(&mut self, scrollbar: bool) {
+        self.scrollbar = scrollbar
+    }
+
+    /// The style of the selected row, black on cyan by default.
+    pub fn set_highlight_style(&mut self, style: Style) {
+        self.selection.highlight_style = style
+    }
+
+    /// Maps a terminal position, e.g. the one from a `MouseEvent`, to the index of the row under it.
+    pub fn row_at(&self, column: usize, row: usize) -> Option<usize> {
+        let inner = self.inner();
+
+        // Clicks on the border do not select anything
+        if !inner.contains(column, row) || row < inner.y + self.y_offset() {
+            return None;
+        }
+
+        let index = row - inner.y - self.y_offset();
+        (index < self.visible_items()).then_some(self.selection.offset + index)
+    }
+
+    fn scroll_to_selected(&mut self) {
+        let offset = self
+            .selection
+            .scrolled_offset(self.items.len(), self.page_height(), |_| 1);
+        self.selection.offset = offset;
+    }
+
+    // How many items fit inside the border
+    fn page_height(&self) -> usize {
+        self.inner().height
+    }
+
+    fn visible_items(&self) -> usize {
+        usize::min(self.items.len() - self.selection.offset, self.page_height())
+    }
+
+    // Where the first item is, relative to the inner area
+    fn y_offset(&self) -> usize {
+        let visible_items = self.visible_items();
+
+        match self.vertical_alignment {
+            VerticalAlignment::Top => 0,
+            VerticalAlignment::Bottom => self.page_height() - visible_items,
+            VerticalAlignment::Center => (self.page_height() - visible_items) / 2,
+        }
     }
 }
 
 impl Widget for ItemList {
-    fn render(&self, terminal: &mut Terminal) {
-        self.area.render(terminal);
+    fn render(&self, target: &mut dyn RenderTarget) {
+        let frame = target.frame();
 
-        // Fast path, there is nothing to render
-        if self.items.is_empty() {
+        self.area.render(frame);
+
+        // Fast path, there is nothing to render or no room for it
+        if self.items.is_empty() || self.page_height() == 0 {
             return;
         }
 
-        let y_offset = match self.vertical_alignment {
-            VerticalAlignment::Top => 1, // 1 for the border
-            VerticalAlignment::Bottom => self.area.height - self.items.len() - 1, // -1 for the border
-            VerticalAlignment::Center => (self.area.height - self.items.len()) / 2,
-        };
+        let inner = self.inner();
+        let y_offset = self.y_offset();
+        let offset = self.selection.offset;
+        let visible_items = offset..offset + self.visible_items();
+
+        let items_width = self
+            .items
+            .iter()
+            .map(|item| width::str_width(item))
+            .max()
+            .unwrap_or(0);
+        // The items that do not fit are cut
+        let items_width = usize::min(items_width, inner.width);
 
         let x_offset = match self.horizontal_alignment {
-            HorizontalAlignment::Left => 1, // 1 for the border
-            HorizontalAlignment::Right => {
-                self.area.width - self.items.iter().map(|item| item.len()).max().unwrap_or(0) - 1
-                // -1 for the border
-            }
-            HorizontalAlignment::Center => {
-                (self.area.width - self.items.iter().map(|item| item.len()).max().unwrap_or(0)) / 2
-            }
+            HorizontalAlignment::Left => 0,
+            HorizontalAlignment::Right => inner.width.saturating_sub(items_width),
+            HorizontalAlignment::Center => inner.width.saturating_sub(items_width) / 2,
         };
 
-        if let Some(selected_row) = self.selected_row {
-            for i in 1..self.width() - 1 {
+        if let Some(selected_row) = self
+            .selection
+            .selected
+            .filter(|selected_row| visible_items.contains(selected_row))
+        {
+            for i in 0..inner.width {
                 let buffer_index =
-                    self.area
-                        .position_to_buffer_index(terminal, i, y_offset + selected_row);
+                    inner.position_to_buffer_index(frame, i, y_offset + selected_row - offset);
 
-                terminal.buffer[buffer_index].background_color = Color::Cyan;
-                terminal.buffer[buffer_index].foreground_color = Color::Black;
+                frame.buffer[buffer_index].set_style(self.selection.highlight_style);
             }
         }
 
-        for (y, item) in self.items.iter().enumerate() {
-            for (x, c) in item.chars().enumerate() {
-                let buffer_index =
-                    self.area
-                        .position_to_buffer_index(terminal, x_offset + x, y_offset + y);
-                terminal.buffer[buffer_index].character = c;
-            }
+        for (y, item) in self.items[visible_items].iter().enumerate() {
+            let item = wrap::truncate(item, items_width, true);
+            inner.put_str(frame, x_offset, y_offset + y, &item, inner.width - x_offset);
+        }
+
+        if self.scrollbar {
+            self.area
+                .render_scrollbar(frame, 0, offset, self.page_height(), self.items.len());
         }
     }
 
@@ -570,9 +1651,40 @@ impl Widget for ItemList {
         self.area.set_border_color(color)
     }
 
+    fn set_border_style(&mut self, style: Style) {
+        self.area.set_border_style(style)
+    }
+
+    fn set_style(&mut self, style: Style) {
+        self.area.set_style(style)
+    }
+
     fn set_title(&mut self, title: Option<String>) {
         self.area.set_title(title);
     }
+
+    fn set_borders(&mut self, borders: Borders) {
+        self.area.set_borders(borders);
+        self.scroll_to_selected();
+    }
+
+    fn set_border_type(&mut self, border_type: BorderType) {
+        self.area.set_border_type(border_type)
+    }
+
+    fn set_padding(&mut self, padding: Spacing) {
+        self.area.set_padding(padding);
+        self.scroll_to_selected();
+    }
+
+    fn set_margin(&mut self, margin: Spacing) {
+        self.area.set_margin(margin);
+        self.scroll_to_selected();
+    }
+
+    fn inner(&self) -> Rectangle {
+        self.area.inner()
+    }
 }
 
 pub struct Table {
@@ -581,7 +1693,22 @@ pub struct Table {
     horizontal_alignment: HorizontalAlignment,
     area: Rectangle,
     column_lengths: Vec<usize>,
-    selected_row: Option<usize>,
+    selection: Selection,
+    header: Option<Vec<String>>,
+    header_style: Style,
+    // The alignment of the content inside each column, columns without one are aligned to the left
+    column_alignments: Vec<HorizontalAlignment>,
+    column_separators: bool,
+    // Columns without a constraint are as wide as their content
+    column_widths: Vec<Constraint>,
+    // How the content that does not fit in its column is handled
+    cell_wrap_mode: WrapMode,
+    scrollbar: bool,
+    horizontal_scrolling: bool,
+    // How many columns of the terminal are scrolled out of view on the left
+    horizontal_offset: usize,
+    // The column the rows are sorted by
+    sort: Option<(usize, SortDirection)>,
 }
 
 impl Table {
@@ -591,98 +1718,579 @@ impl Table {
         horizontal_alignment: HorizontalAlignment,
         area: Rectangle,
     ) -> Table {
-        let max_row_size = items.iter().map(|row| row.len()).max().unwrap();
+        let mut table = Table {
+            items,
+            vertical_alignment,
+            horizontal_alignment,
+            area,
+            column_lengths: Vec::new(),
+            selection: Selection::new(),
+            header: None,
+            header_style: Style::default().modifiers(Modifier::BOLD),
+            column_alignments: Vec::new(),
+            column_separators: false,
+            column_widths: Vec::new(),
+            cell_wrap_mode: WrapMode::Ellipsis,
+            scrollbar: false,
+            horizontal_scrolling: false,
+            horizontal_offset: 0,
+            sort: None,
+        };
+        table.update_column_lengths();
+
+        table
+    }
+
+    /// Selects the row, scrolling the table so it's in view.
+    pub fn set_selected(&mut self, row_index: Option<usize>) {
+        self.selection.select(row_index, self.items.len());
+        self.scroll_to_selected();
+    }
+
+    pub fn selected(&self) -> Option<usize> {
+        self.selection.selected
+    }
+
+    /// Selects the row below the selected one, or the first one if nothing is selected.
+    pub fn select_next(&mut self) {
+        self.selection.select_next(self.items.len());
+        self.scroll_to_selected();
+    }
+
+    /// Selects the row above the selected one, or the last one if nothing is selected.
+    pub fn select_previous(&mut self) {
+        self.selection.select_previous(self.items.len());
+        self.scroll_to_selected();
+    }
 
-        let mut column_lengths = vec![0; max_row_size];
-        for row in items.iter() {
+    /// Moves the selection as many rows up as fit in the table, stopping at the first row.
+    pub fn page_up(&mut self) {
+        self.selection.page_up(self.page_rows(), self.items.len());
+        self.scroll_to_selected();
+    }
+
+    /// Moves the selection as many rows down as fit in the table, stopping at the last row.
+    pub fn page_down(&mut self) {
+        self.selection.page_down(self.page_rows(), self.items.len());
+        self.scroll_to_selected();
+    }
+
+    pub fn select_first(&mut self) {
+        self.set_selected(Some(0));
+    }
+
+    pub fn select_last(&mut self) {
+        self.set_selected(Some(self.items.len().saturating_sub(1)));
+    }
+
+    /// Makes `select_next` on the last row select the first one, and `select_previous` on the
+    /// first row select the last one. Disabled by default.
+    pub fn set_wrap_around(&mut self, wrap_around: bool) {
+        self.selection.wrap_around = wrap_around
+    }
+
+    /// Shows the scroll position on the right border when the rows do not fit.
+    pub fn set_scrollbar(&mut self, scrollbar: bool) {
+        self.scrollbar = scrollbar
+    }
+
+    /// The rows in view, which are the only ones that need to be up to date when rendering.
+    pub fn visible_rows(&self) -> std::ops::Range<usize> {
+        let offset = self.selection.offset;
+        let mut height = 0;
+        let mut end = offset;
+
+        while end < self.items.len() && height < self.page_height() {
+            height += self.row_height(end);
+            end += 1;
+        }
+
+        offset..end
+    }
+
+    /// Keeps the columns as wide as their constraints ask for even if the table does not fit,
+    /// instead of shrinking them, so the hidden part can be scrolled into view with
+    /// `scroll_right` and `scroll_left`.
+    pub fn set_horizontal_scrolling(&mut self, horizontal_scrolling: bool) {
+        self.horizontal_scrolling = horizontal_scrolling;
+        self.horizontal_offset = 0;
+        self.update_column_lengths();
+    }
+
+    pub fn scroll_left(&mut self, columns: usize) {
+        self.horizontal_offset = self.horizontal_offset.saturating_sub(columns);
+    }
+
+    pub fn scroll_right(&mut self, columns: usize) {
+        let max_offset = self.required_width().saturating_sub(self.inner().width);
+        self.horizontal_offset = usize::min(self.horizontal_offset + columns, max_offset);
+    }
+
+    /// The style of the selected row, black on cyan by default.
+    pub fn set_highlight_style(&mut self, style: Style) {
+        self.selection.highlight_style = style
+    }
+
+    /// Sorts the rows by the content of the column, comparing numbers by their value, and marks
+    /// the column in the header. Rows with the same content keep their order, and the selection
+    /// follows the selected row to its new position.
+    pub fn sort_by(&mut self, column_index: usize, direction: SortDirection) {
+        // Rows without the column sort as if it was empty
+        let cell = |row_index: usize| {
+            self.items[row_index]
+                .get(column_index)
+                .map_or("", |item| item.as_str())
+        };
+
+        let mut order: Vec<usize> = (0..self.items.len()).collect();
+        order.sort_by(|a, b| {
+            let ordering = sort::natural_cmp(cell(*a), cell(*b));
+
+            match direction {
+                SortDirection::Ascending => ordering,
+                SortDirection::Descending => ordering.reverse(),
+            }
+        });
+
+        let mut items: Vec<Option<Vec<String>>> = std::mem::take(&mut self.items)
+            .into_iter()
+            .map(Some)
+            .collect();
+        self.items = order
+            .iter()
+            .map(|index| items[*index].take().expect("the order is a permutation"))
+            .collect();
+
+        self.selection.selected = self
+            .selection
+            .selected
+            .and_then(|selected| order.iter().position(|index| *index == selected));
+
+        self.sort = Some((column_index, direction));
+        // The indicator makes the header wider
+        self.update_column_lengths();
+        self.scroll_to_selected();
+    }
+
+    /// The column the rows are sorted by and in which direction, if they are sorted.
+    pub fn sort(&self) -> Option<(usize, SortDirection)> {
+        self.sort
+    }
+
+    /// The header is drawn above the rows with a rule underneath, and stays in place when they
+    /// scroll.
+    pub fn set_header(&mut self, header: Option<Vec<String>>) {
+        self.header = header;
+        self.update_column_lengths();
+        self.scroll_to_selected();
+    }
+
+    /// The style of the header, bold by default.
+    pub fn set_header_style(&mut self, style: Style) {
+        self.header_style = style
+    }
+
+    /// Aligns the content of each column inside it, e.g. numbers are easier to compare when
+    /// aligned to the right. Columns without an alignment are aligned to the left.
+    pub fn set_column_alignments(&mut self, alignments: Vec<HorizontalAlignment>) {
+        self.column_alignments = alignments
+    }
+
+    /// Constrains the width of each column, which otherwise is as wide as its content. The content
+    /// is bounded by `Min` and `Max`, while the others ignore it.
+    ///
+    /// When the columns do not fit in the table, the widest ones are shrunk, and the content that
+    /// does not fit is handled according to `set_cell_wrap_mode`.
+    pub fn set_column_widths(&mut self, widths: Vec<Constraint>) {
+        self.column_widths = widths;
+        self.update_column_lengths();
+        self.scroll_to_selected();
+    }
+
+    /// The content that does not fit in its column is cut with an ellipsis by default. Wrapping it
+    /// makes the row as tall as the cell with the most lines, while the header is always cut.
+    pub fn set_cell_wrap_mode(&mut self, wrap_mode: WrapMode) {
+        self.cell_wrap_mode = wrap_mode;
+        self.scroll_to_selected();
+    }
+
+    /// Draws vertical lines between the columns, joined to the border and the header rule.
+    pub fn set_column_separators(&mut self, column_separators: bool) {
+        self.column_separators = column_separators;
+        self.update_column_lengths();
+        self.scroll_to_selected();
+    }
+
+    /// Maps a terminal position, e.g. the one from a `MouseEvent`, to the index of the row under it.
+    pub fn row_at(&self, column: usize, row: usize) -> Option<usize> {
+        let inner = self.inner();
+        let first_row = self.y_offset() + self.header_height();
+
+        // Clicks on the border or the header do not select anything
+        if !inner.contains(column, row) || row < inner.y + first_row {
+            return None;
+        }
+
+        let mut line = row - inner.y - first_row;
+        for index in self.visible_rows() {
+            let height = self.row_height(index);
+            if line < height {
+                return Some(index);
+            }
+            line -= height;
+        }
+
+        None
+    }
+
+    fn scroll_to_selected(&mut self) {
+        // Wrapped rows take more than a line each
+        let row_height = |index| self.row_height(index);
+        let offset =
+            self.selection
+                .scrolled_offset(self.items.len(), self.page_height(), row_height);
+        self.selection.offset = offset;
+    }
+
+    fn update_column_lengths(&mut self) {
+        let max_row_size = self
+            .items
+            .iter()
+            .chain(&self.header)
+            .map(|row| row.len())
+            .max()
+            .unwrap_or(0);
+
+        let mut content_widths = vec![0; max_row_size];
+        for row in self.items.iter().chain(&self.header) {
             for (i, item) in row.iter().enumerate() {
-                if item.len() > column_lengths[i] {
-                    column_lengths[i] = item.len();
-                }
+                content_widths[i] = usize::max(content_widths[i], width::str_width(item));
             }
         }
 
-        let required_width: usize = column_lengths.iter().sum();
+        if let (Some((column_index, _)), Some(header)) = (self.sort, &self.header) {
+            if let Some(name) = header.get(column_index) {
+                // +2 for the space and the sort indicator
+                content_widths[column_index] =
+                    usize::max(content_widths[column_index], width::str_width(name) + 2);
+            }
+        }
 
-        assert!((items.len()) <= area.height - 2); // -2 for the border
-        assert!(required_width < area.width - 2); // -2 for the border
+        let constraints: Vec<Constraint> = content_widths
+            .iter()
+            .enumerate()
+            .map(|(i, content_width)| match self.column_widths.get(i) {
+                None => Constraint::Length(*content_width),
+                Some(Constraint::Min(min)) => Constraint::Min(usize::max(*min, *content_width)),
+                Some(Constraint::Max(max)) => Constraint::Length(usize::min(*max, *content_width)),
+                Some(constraint) => *constraint,
+            })
+            .collect();
+
+        let gaps = self.gap() * max_row_size.saturating_sub(1);
+        let mut available_width = self.inner().width.saturating_sub(gaps);
+
+        if self.horizontal_scrolling {
+            // As wide as the columns ask for, the ones that take what is left do not ask for any
+            let natural_width = constraints
+                .iter()
+                .map(|constraint| match constraint {
+                    Constraint::Length(length) | Constraint::Min(length) => *length,
+                    Constraint::Max(max) => *max,
+                    Constraint::Percentage(_) | Constraint::Ratio(..) | Constraint::Fill(_) => 0,
+                })
+                .sum();
+
+            available_width = usize::max(available_width, natural_width);
+        }
 
-        Table {
-            items,
-            vertical_alignment,
-            horizontal_alignment,
-            area,
-            column_lengths,
-            selected_row: None,
+        self.column_lengths = Constraint::resolve(&constraints, available_width);
+    }
+
+    // How many lines the row takes, with the content wrapped to the width of the columns
+    fn row_height(&self, row_index: usize) -> usize {
+        if let WrapMode::Truncate | WrapMode::Ellipsis = self.cell_wrap_mode {
+            return 1;
         }
+
+        self.items[row_index]
+            .iter()
+            .zip(&self.column_lengths)
+            .map(|(item, length)| WrappedLines::new(item, *length, self.cell_wrap_mode).count())
+            .max()
+            .unwrap_or(0)
+            .max(1)
     }
 
-    pub fn set_selected(&mut self, row_index: Option<usize>) {
-        self.selected_row = row_index
+    // How many lines there are for the rows, inside the border and below the header
+    fn page_height(&self) -> usize {
+        self.inner().height.saturating_sub(self.header_height())
+    }
+
+    // How many rows a page up or down moves
+    fn page_rows(&self) -> usize {
+        usize::max(1, self.visible_rows().len())
+    }
+
+    // The space between two columns, with the separator in the middle if there is one
+    fn gap(&self) -> usize {
+        if self.column_separators {
+            3
+        } else {
+            1
+        }
+    }
+
+    fn required_width(&self) -> usize {
+        self.column_lengths.iter().sum::<usize>()
+            + self.gap() * self.column_lengths.len().saturating_sub(1)
+    }
+
+    // Where the column starts, relative to the first one
+    fn column_x(&self, column_index: usize) -> usize {
+        self.column_lengths.iter().take(column_index).sum::<usize>() + self.gap() * column_index
+    }
+
+    // The name of the column followed by the sort indicator if the rows are sorted by it
+    fn header_label(&self, column_index: usize, name: &str) -> String {
+        match self.sort {
+            Some((sorted_column, direction)) if sorted_column == column_index => {
+                // The name is cut instead of the indicator if the column is too narrow, -2 for the
+                // space and the indicator
+                let length = self.column_lengths[column_index];
+                let name = wrap::truncate(name, length.saturating_sub(2), true);
+
+                format!("{name} {}", direction.indicator())
+            }
+            _ => name.to_string(),
+        }
+    }
+
+    // The header and the rule underneath it
+    fn header_height(&self) -> usize {
+        if self.header.is_some() {
+            2
+        } else {
+            0
+        }
+    }
+
+    fn y_offset(&self) -> usize {
+        let rows_height = self
+            .visible_rows()
+            .map(|index| self.row_height(index))
+            .sum::<usize>();
+
+        let inner_height = self.inner().height;
+        let height = usize::min(self.header_height() + rows_height, inner_height);
+
+        match self.vertical_alignment {
+            VerticalAlignment::Top => 0,
+            VerticalAlignment::Bottom => inner_height - height,
+            VerticalAlignment::Center => (inner_height - height) / 2,
+        }
+    }
+
+    fn x_offset(&self) -> usize {
+        let inner_width = self.inner().width;
+        // Scrolled tables are wider than the area, there is nothing to align
+        let required_width = usize::min(self.required_width(), inner_width);
+
+        match self.horizontal_alignment {
+            HorizontalAlignment::Left => 0,
+            HorizontalAlignment::Right => inner_width - required_width,
+            HorizontalAlignment::Center => (inner_width - required_width) / 2,
+        }
+    }
+
+    // Whether the columns between `x` and `x + width`, before scrolling, are inside the inner area
+    fn is_visible(&self, x: usize, width: usize) -> bool {
+        x >= self.horizontal_offset && x + width <= self.horizontal_offset + self.inner().width
+    }
+
+    // Same as `Rectangle::put_str`, but the position is shifted by the horizontal scrolling and
+    // what ends up outside of the border is not drawn
+    fn put_str(&self, frame: &mut Frame, x: usize, y: usize, text: &str) {
+        let mut x = x;
+
+        for grapheme in Graphemes::new(text) {
+            let grapheme_width = width::grapheme_width(grapheme);
+
+            if grapheme_width > 0 && self.is_visible(x, grapheme_width) {
+                let buffer_index =
+                    self.inner()
+                        .position_to_buffer_index(frame, x - self.horizontal_offset, y);
+                frame.put_symbol(buffer_index, grapheme);
+            }
+
+            x += grapheme_width;
+        }
+    }
+
+    fn render_row(
+        &self,
+        frame: &mut Frame,
+        x_offset: usize,
+        y: usize,
+        row: &[String],
+        wrap_mode: WrapMode,
+    ) {
+        for (column_index, item) in row.iter().enumerate() {
+            let column_length = self.column_lengths[column_index];
+
+            let alignment = self
+                .column_alignments
+                .get(column_index)
+                .copied()
+                .unwrap_or(HorizontalAlignment::Left);
+
+            let lines = WrappedLines::new(item, column_length, wrap_mode);
+            for (line_index, line) in lines.take(self.inner().height - y).enumerate() {
+                let line_width = width::str_width(&line);
+
+                let x = x_offset
+                    + self.column_x(column_index)
+                    + match alignment {
+                        HorizontalAlignment::Left => 0,
+                        HorizontalAlignment::Right => column_length - line_width,
+                        HorizontalAlignment::Center => (column_length - line_width) / 2,
+                    };
+
+                self.put_str(frame, x, y + line_index, &line);
+            }
+        }
+    }
+
+    // The lines go from border to border, joining them if there is one
+    fn render_separators(&self, frame: &mut Frame, x_offset: usize, rule_y: Option<usize>) {
+        let border_box = self.area.border_box();
+        let inner = self.inner();
+        let borders = self.area.borders;
+        let symbols = self.area.border_type.symbols();
+
+        let put = |frame: &mut Frame, x: usize, y: usize, symbol: &str| {
+            let buffer_index = border_box.position_to_buffer_index(frame, x, y);
+            self.area.put_border_symbol(frame, buffer_index, symbol);
+        };
+
+        // From the inner area to the border box
+        let rule_y = rule_y.map(|rule_y| inner.y - border_box.y + rule_y);
+
+        if let Some(rule_y) = rule_y {
+            for x in 0..border_box.width {
+                let symbol = if x == 0 && borders.contains(Borders::LEFT) {
+                    symbols.left_junction
+                } else if x == border_box.width - 1 && borders.contains(Borders::RIGHT) {
+                    symbols.right_junction
+                } else {
+                    symbols.horizontal
+                };
+                put(frame, x, rule_y, symbol);
+            }
+        }
+
+        if !self.column_separators {
+            return;
+        }
+
+        for column_index in 0..self.column_lengths.len().saturating_sub(1) {
+            // In the middle of the gap after the column
+            let x = x_offset + self.column_x(column_index) + self.column_lengths[column_index] + 1;
+
+            if !self.is_visible(x, 1) {
+                continue;
+            }
+            let x = inner.x - border_box.x + x - self.horizontal_offset;
+
+            for y in 0..border_box.height {
+                let symbol = if y == 0 && borders.contains(Borders::TOP) {
+                    symbols.top_junction
+                } else if y == border_box.height - 1 && borders.contains(Borders::BOTTOM) {
+                    symbols.bottom_junction
+                } else if Some(y) == rule_y {
+                    symbols.cross
+                } else {
+                    symbols.vertical
+                };
+                put(frame, x, y, symbol);
+            }
+        }
     }
 }
 
 impl Widget for Table {
-    fn render(&self, terminal: &mut Terminal) {
-        self.area.render(terminal);
+    fn render(&self, target: &mut dyn RenderTarget) {
+        let frame = target.frame();
+
+        self.area.render(frame);
 
         // Fast path, there is nothing to render
-        if self.items.is_empty() {
+        if self.items.is_empty() && self.header.is_none() {
             return;
         }
 
-        let y_offset = match self.vertical_alignment {
-            VerticalAlignment::Top => 1, // 1 for the border
-            VerticalAlignment::Bottom => self.area.height - self.items.len() - 1, // -1 for the border
-            VerticalAlignment::Center => (self.area.height - self.items.len()) / 2,
-        };
-
-        let x_offset = match self.horizontal_alignment {
-            HorizontalAlignment::Left => 1, // 1 for the border
-            HorizontalAlignment::Right => {
-                // -1 for the border
-                self.area.width
-                    - self.column_lengths.iter().sum::<usize>()
-                    - 1
-                    // For the spacing between columns
-                    - self.column_lengths.len() - 1
-            }
-            HorizontalAlignment::Center => {
-                (self.area.width
-                    - self.column_lengths.iter().sum::<usize>()
-                    // For the spacing between columns
-                    - self.column_lengths.len()
-                    - 1)
-                    / 2
+        let inner = self.inner();
+        let y_offset = self.y_offset();
+        let rows_y_offset = y_offset + self.header_height();
+        let x_offset = self.x_offset();
+
+        // The header and the rule below it are left out where there is no room for them
+        let rule_y = self
+            .header
+            .as_ref()
+            .map(|_| y_offset + 1)
+            .filter(|rule_y| *rule_y < inner.height);
+        self.render_separators(frame, x_offset, rule_y);
+        // The separators join the top border, but the title stays on top of them
+        self.area.render_title(frame);
+
+        if let Some(header) = self.header.as_ref().filter(|_| y_offset < inner.height) {
+            for i in 0..inner.width {
+                let buffer_index = inner.position_to_buffer_index(frame, i, y_offset);
+                frame.buffer[buffer_index].set_style(self.header_style);
             }
-        };
 
-        if let Some(selected_row) = self.selected_row {
-            for i in 1..self.width() - 1 {
-                let buffer_index =
-                    self.area
-                        .position_to_buffer_index(terminal, i, y_offset + selected_row);
+            let header: Vec<String> = header
+                .iter()
+                .enumerate()
+                .map(|(column_index, name)| self.header_label(column_index, name))
+                .collect();
 
-                terminal.buffer[buffer_index].background_color = Color::Cyan;
-                terminal.buffer[buffer_index].foreground_color = Color::Black;
-            }
+            self.render_row(frame, x_offset, y_offset, &header, WrapMode::Ellipsis);
         }
 
-        for (row_index, row) in self.items.iter().enumerate() {
-            for (column_index, item) in row.iter().enumerate() {
-                for (k, c) in item.chars().enumerate() {
-                    // We sum the 'column_index' in the end to add gaps
-                    let x =
-                        self.column_lengths.iter().take(column_index).sum::<usize>() + column_index;
+        let mut y = rows_y_offset;
+        for row_index in self.visible_rows() {
+            let row_height = self.row_height(row_index);
 
-                    let buffer_index = self.area.position_to_buffer_index(
-                        terminal,
-                        x_offset + x + k,
-                        y_offset + row_index,
-                    );
-                    terminal.buffer[buffer_index].character = c;
+            if self.selection.selected == Some(row_index) {
+                for line in y..usize::min(y + row_height, inner.height) {
+                    for i in 0..inner.width {
+                        let buffer_index = inner.position_to_buffer_index(frame, i, line);
+                        frame.buffer[buffer_index].set_style(self.selection.highlight_style);
+                    }
                 }
             }
+
+            self.render_row(
+                frame,
+                x_offset,
+                y,
+                &self.items[row_index],
+                self.cell_wrap_mode,
+            );
+            y += row_height;
+        }
+
+        if self.scrollbar {
+            // The scrollbar covers the rows only, the header does not scroll
+            self.area.render_scrollbar(
+                frame,
+                rows_y_offset,
+                self.selection.offset,
+                self.visible_rows().len(),
+                self.items.len(),
+            );
         }
     }
 
@@ -698,97 +2306,981 @@ impl Widget for Table {
         self.area.set_border_color(color)
     }
 
+    fn set_border_style(&mut self, style: Style) {
+        self.area.set_border_style(style)
+    }
+
+    fn set_style(&mut self, style: Style) {
+        self.area.set_style(style)
+    }
+
     fn set_title(&mut self, title: Option<String>) {
         self.area.set_title(title);
     }
+
+    fn set_borders(&mut self, borders: Borders) {
+        self.area.set_borders(borders);
+        self.update_column_lengths();
+        self.scroll_to_selected();
+    }
+
+    fn set_border_type(&mut self, border_type: BorderType) {
+        self.area.set_border_type(border_type)
+    }
+
+    fn set_padding(&mut self, padding: Spacing) {
+        self.area.set_padding(padding);
+        self.update_column_lengths();
+        self.scroll_to_selected();
+    }
+
+    fn set_margin(&mut self, margin: Spacing) {
+        self.area.set_margin(margin);
+        self.update_column_lengths();
+        self.scroll_to_selected();
+    }
+
+    fn inner(&self) -> Rectangle {
+        self.area.inner()
+    }
 }
 
-#[derive(Copy, Clone)]
-struct Cell {
-    character: char,
+// Longer grapheme clusters are cut at a char boundary, it's enough for the longest emoji sequences
+const SYMBOL_CAPACITY: usize = 32;
+
+#[derive(Copy, Clone, PartialEq, Eq)]
+pub struct Cell {
+    // The grapheme cluster drawn in the cell, encoded as UTF-8. It's empty when the cell is the
+    // second column of a wide cluster drawn in the cell on its left.
+    symbol: [u8; SYMBOL_CAPACITY],
+    symbol_length: u8,
     foreground_color: Color,
     background_color: Color,
+    modifiers: Modifier,
+}
+
+impl Cell {
+    /// The grapheme cluster drawn in the cell, empty when the cell is covered by a wide one.
+    pub fn symbol(&self) -> &str {
+        // It's only written by 'set_symbol', which keeps it valid
+        std::str::from_utf8(&self.symbol[..self.symbol_length as usize]).unwrap()
+    }
+
+    /// Whether the cell is the second column of a wide grapheme cluster, e.g. '中', drawn in the
+    /// cell on its left.
+    pub fn is_continuation(&self) -> bool {
+        self.symbol_length == 0
+    }
+
+    pub fn foreground_color(&self) -> Color {
+        self.foreground_color
+    }
+
+    pub fn background_color(&self) -> Color {
+        self.background_color
+    }
+
+    pub fn modifiers(&self) -> Modifier {
+        self.modifiers
+    }
+
+    pub fn style(&self) -> Style {
+        Style {
+            foreground_color: self.foreground_color,
+            background_color: self.background_color,
+            modifiers: self.modifiers,
+        }
+    }
+
+    fn set_symbol(&mut self, symbol: &str) {
+        let mut length = symbol.len().min(SYMBOL_CAPACITY);
+        while !symbol.is_char_boundary(length) {
+            length -= 1;
+        }
+
+        // The unused bytes are cleared so cells with the same symbol compare equal
+        self.symbol = [0; SYMBOL_CAPACITY];
+        self.symbol[..length].copy_from_slice(&symbol.as_bytes()[..length]);
+        self.symbol_length = length as u8;
+    }
+
+    fn set_continuation(&mut self) {
+        self.symbol = [0; SYMBOL_CAPACITY];
+        self.symbol_length = 0;
+    }
+
+    fn set_style(&mut self, style: Style) {
+        self.foreground_color = style.foreground_color;
+        self.background_color = style.background_color;
+        self.modifiers = style.modifiers;
+    }
 }
 
 impl Default for Cell {
     fn default() -> Self {
-        Cell {
-            character: ' ',
+        let mut cell = Cell {
+            symbol: [0; SYMBOL_CAPACITY],
+            symbol_length: 0,
             foreground_color: Color::Default,
             background_color: Color::Default,
-        }
+            modifiers: Modifier::NONE,
+        };
+        cell.set_symbol(" ");
+
+        cell
     }
 }
 
-#[derive(Copy, Clone, PartialEq, Eq)]
-pub enum Color {
-    // User's terminal default color
-    Black,
-    Cyan,
-    Default,
-    Green,
+impl std::fmt::Debug for Cell {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Cell")
+            .field("symbol", &self.symbol())
+            .field("foreground_color", &self.foreground_color)
+            .field("background_color", &self.background_color)
+            .field("modifiers", &self.modifiers)
+            .finish()
+    }
 }
 
-impl Color {
-    fn apply_foreground(&self) {
-        match self {
-            Color::Black => print!("\x1b[30m"),
-            Color::Cyan => print!("\x1b[36m"),
-            Color::Default => print!("\x1b[39m"),
-            Color::Green => print!("\x1b[32m"),
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::random::Random;
+
+    fn frame(width: usize, lines: &[&str]) -> Vec<Cell> {
+        let mut buffer = Vec::new();
+        for line in lines {
+            assert_eq!(line.chars().count(), width);
+            buffer.extend(line.chars().map(|character| {
+                let mut cell = Cell::default();
+                cell.set_symbol(character.encode_utf8(&mut [0; 4]));
+                cell
+            }));
         }
+        buffer
+    }
+
+    fn diff(previous: &[Cell], current: &[Cell], width: usize) -> String {
+        let mut output = Vec::new();
+        Terminal::<Vec<u8>>::write_diff(
+            previous,
+            current,
+            width,
+            ColorSupport::TrueColor,
+            &mut output,
+        )
+        .unwrap();
+        String::from_utf8(output).unwrap()
+    }
+
+    #[test]
+    fn diff_full_redraw_when_there_is_no_previous_frame() {
+        let current = frame(3, &["abc", "def"]);
+
+        assert_eq!(
+            diff(&[], &current, 3),
+            "\x1b[2J\x1b[1;1H\x1b[0mabc\x1b[2;1Hdef"
+        );
+    }
+
+    #[test]
+    fn diff_identical_frames_emit_nothing() {
+        let current = frame(3, &["abc", "def"]);
+
+        assert_eq!(diff(&current, &current, 3), "");
+    }
+
+    #[test]
+    fn diff_single_changed_cell() {
+        let previous = frame(3, &["abc", "def"]);
+        let current = frame(3, &["abc", "dXf"]);
+
+        assert_eq!(diff(&previous, &current, 3), "\x1b[2;2H\x1b[0mX");
+    }
+
+    #[test]
+    fn diff_contiguous_run_only_moves_the_cursor_once() {
+        let previous = frame(5, &["abcde", "fghij"]);
+        let current = frame(5, &["aXYZe", "fghiW"]);
+
+        assert_eq!(
+            diff(&previous, &current, 5),
+            "\x1b[1;2H\x1b[0mXYZ\x1b[2;5HW"
+        );
     }
 
-    fn apply_background(&self) {
-        match self {
-            Color::Black => print!("\x1b[40m"),
-            Color::Cyan => print!("\x1b[46m"),
-            Color::Default => print!("\x1b[49m"),
-            Color::Green => print!("\x1b[42m"),
+    #[test]
+    fn diff_does_not_rely_on_the_cursor_wrapping_to_the_next_line() {
+        let previous = frame(2, &["ab", "cd"]);
+        let current = frame(2, &["aX", "Yd"]);
+
+        assert_eq!(diff(&previous, &current, 2), "\x1b[1;2H\x1b[0mX\x1b[2;1HY");
+    }
+
+    #[test]
+    fn diff_color_only_changes() {
+        let previous = frame(3, &["abc"]);
+        let mut current = previous.clone();
+        current[1].foreground_color = Color::Green;
+        current[2].foreground_color = Color::Green;
+        current[2].background_color = Color::Black;
+
+        assert_eq!(
+            diff(&previous, &current, 3),
+            "\x1b[1;2H\x1b[0;32mb\x1b[40mc"
+        );
+    }
+
+    #[test]
+    fn diff_modifier_changes() {
+        let previous = frame(4, &["abcd"]);
+        let mut current = previous.clone();
+        current[0].modifiers = Modifier::BOLD;
+        current[1].modifiers = Modifier::BOLD | Modifier::UNDERLINE;
+        current[2].modifiers = Modifier::UNDERLINE;
+
+        assert_eq!(
+            diff(&previous, &current, 4),
+            "\x1b[1;1H\x1b[0;1ma\x1b[4mb\x1b[22mc"
+        );
+    }
+
+    #[test]
+    fn diff_wide_characters_skip_their_continuation() {
+        let mut terminal = Terminal::headless(4, 1);
+        terminal.frame.put_symbol(0, "中");
+        terminal.frame.put_symbol(2, "a");
+
+        assert_eq!(
+            diff(&[], &terminal.frame.buffer, 4),
+            "\x1b[2J\x1b[1;1H\x1b[0m中a "
+        );
+    }
+
+    #[test]
+    fn put_symbol_keeps_wide_characters_whole() {
+        let mut terminal = Terminal::headless(5, 1);
+
+        terminal.frame.put_symbol(0, "中");
+        terminal.frame.put_symbol(2, "文");
+        assert_eq!(terminal.dump_buffer(), "中文 \n");
+        assert!(terminal.cell(1, 0).is_continuation());
+
+        // Overwriting the second half blanks the first one
+        terminal.frame.put_symbol(1, "a");
+        assert_eq!(terminal.dump_buffer(), " a文 \n");
+
+        // Overwriting the first half blanks the second one
+        terminal.frame.put_symbol(2, "b");
+        assert_eq!(terminal.dump_buffer(), " ab  \n");
+
+        // A wide character straddling another one blanks what is left of it
+        terminal.frame.put_symbol(3, "中");
+        terminal.frame.put_symbol(2, "文");
+        assert_eq!(terminal.dump_buffer(), " a文 \n");
+
+        // There is no room for it in the last column
+        terminal.frame.put_symbol(4, "中");
+        assert_eq!(terminal.dump_buffer(), " a文 \n");
+        assert_eq!(terminal.cell(4, 0).symbol(), " ");
+    }
+
+    #[test]
+    fn draw_errors_reach_the_caller() {
+        struct Closed;
+
+        impl Write for Closed {
+            fn write(&mut self, _: &[u8]) -> std::io::Result<usize> {
+                Err(std::io::ErrorKind::BrokenPipe.into())
+            }
+
+            fn flush(&mut self) -> std::io::Result<()> {
+                Ok(())
+            }
         }
+
+        let mut terminal = Terminal {
+            output: Closed,
+            frame: Frame::new(3, 1),
+            previous_buffer: vec![Cell::default(); 3],
+            output_buffer: Vec::new(),
+            tty: None,
+            input: Vec::new(),
+            partial_since: None,
+            mouse_capture: false,
+            alternate_screen: false,
+            color_support: ColorSupport::TrueColor,
+        };
+        terminal.frame.put_symbol(1, "b");
+
+        let err = terminal.draw().unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::BrokenPipe);
+        assert!(terminal.output_buffer.is_empty());
+        assert!(terminal.previous_buffer.is_empty());
     }
-}
 
-struct HardwrappingText<'a> {
-    text: &'a [char],
-    width: usize,
-}
+    #[test]
+    fn resize_repaints_everything() {
+        let mut terminal = Terminal::headless(3, 1);
+        terminal.frame.put_symbol(0, "a");
+        terminal.draw().unwrap();
+        terminal.output.clear();
+
+        terminal.resize(4, 2);
+        assert_eq!(terminal.frame.buffer.len(), 8);
+        assert_eq!(terminal.dump_buffer(), "    \n    \n");
+
+        terminal.frame.put_symbol(5, "b");
+        terminal.draw().unwrap();
+        let output = String::from_utf8(terminal.output.clone()).unwrap();
+        assert_eq!(output, "\x1b[2J\x1b[1;1H\x1b[0m    \x1b[2;1H b  ");
+        assert_eq!(terminal.previous_buffer.len(), 8);
+    }
+
+    // A terminal reading its input from a pipe, written through the returned end
+    fn piped_terminal() -> (Terminal<Vec<u8>>, std::fs::File) {
+        use std::os::fd::FromRawFd;
+
+        let mut fds = [0; 2];
+        assert_eq!(unsafe { libc::pipe(fds.as_mut_ptr()) }, 0);
+        let (input, writer) = unsafe {
+            (
+                std::fs::File::from_raw_fd(fds[0]),
+                std::fs::File::from_raw_fd(fds[1]),
+            )
+        };
 
-impl<'a> HardwrappingText<'a> {
-    pub fn new(text: &'a [char], width: usize) -> Self {
-        Self { text, width }
+        let mut terminal = Terminal::headless(10, 5);
+        terminal.tty = Some(Tty {
+            file: input,
+            termios: unsafe { std::mem::zeroed() },
+            previous_sigwinch_action: unsafe { std::mem::zeroed() },
+        });
+
+        (terminal, writer)
     }
-}
 
-impl<'a> Iterator for HardwrappingText<'a> {
-    type Item = &'a [char];
+    #[test]
+    fn read_event_fails_once_the_terminal_is_closed() {
+        let (mut terminal, mut writer) = piped_terminal();
+        writer.write_all(b"a").unwrap();
+        drop(writer);
+
+        assert_eq!(
+            terminal.read_event().unwrap(),
+            Event::Key(KeyEvent::new(KeyCode::Char('a'), KeyModifiers::NONE))
+        );
+        let err = terminal.read_event().unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::UnexpectedEof);
+    }
 
-    fn next(&mut self) -> Option<Self::Item> {
-        if self.text.is_empty() {
-            return None;
+    #[test]
+    fn lone_escape_across_polls() {
+        let (mut terminal, mut writer) = piped_terminal();
+        let key = |code| Some(Event::Key(KeyEvent::new(code, KeyModifiers::NONE)));
+
+        writer.write_all(b"\x1b").unwrap();
+        let start = Instant::now();
+        let event = loop {
+            if let Some(event) = terminal.poll_event(Duration::ZERO).unwrap() {
+                break Some(event);
+            }
+            assert!(start.elapsed() < Duration::from_secs(5));
+            std::thread::sleep(Duration::from_millis(5));
+        };
+        assert_eq!(event, key(KeyCode::Esc));
+        assert!(start.elapsed() >= ESCAPE_TIMEOUT);
+
+        // The next key is not taken for Alt + key
+        writer.write_all(b"a").unwrap();
+        assert_eq!(
+            terminal.poll_event(Duration::from_secs(5)).unwrap(),
+            key(KeyCode::Char('a'))
+        );
+
+        // A sequence split between polls is still a sequence
+        writer.write_all(b"\x1b").unwrap();
+        assert_eq!(terminal.poll_event(Duration::ZERO).unwrap(), None);
+        writer.write_all(b"[A").unwrap();
+        assert_eq!(
+            terminal.poll_event(Duration::ZERO).unwrap(),
+            key(KeyCode::Up)
+        );
+    }
+
+    #[test]
+    fn poll_event_returns_the_input_already_read() {
+        let mut terminal = Terminal::headless(3, 1);
+        terminal.input.extend_from_slice(b"a\x1b[A");
+
+        let key = |code| Some(Event::Key(KeyEvent::new(code, KeyModifiers::NONE)));
+        assert_eq!(
+            terminal.poll_event(Duration::ZERO).unwrap(),
+            key(KeyCode::Char('a'))
+        );
+        assert_eq!(
+            terminal.poll_event(Duration::from_secs(60)).unwrap(),
+            key(KeyCode::Up)
+        );
+
+        // Waiting for more needs a tty
+        let err = terminal.poll_event(Duration::ZERO).unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::Unsupported);
+    }
+
+    #[test]
+    fn alternate_screen() {
+        let output = |terminal: &mut Terminal<Vec<u8>>| {
+            terminal.flush_output().unwrap();
+            String::from_utf8(std::mem::take(&mut terminal.output)).unwrap()
+        };
+
+        let mut terminal = Terminal::headless(3, 2);
+        terminal.alternate_screen = true;
+        terminal.enter_screen();
+        assert_eq!(output(&mut terminal), "\x1b[?1049h\x1b[?25l");
+        terminal.leave_screen();
+        assert_eq!(output(&mut terminal), "\x1b[?1049l\x1b[?25h");
+
+        // On the main screen the last frame stays, with the cursor below it
+        terminal.alternate_screen = false;
+        terminal.enter_screen();
+        assert_eq!(output(&mut terminal), "\x1b[?25l");
+        terminal.leave_screen();
+        assert_eq!(output(&mut terminal), "\x1b[2;1H\r\n\x1b[?25h");
+    }
+
+    #[test]
+    fn widgets_render_into_any_target() {
+        let mut terminal = Terminal::headless(8, 3);
+        let (left, right) = terminal.area().split_horizontally();
+        let widgets: Vec<Box<dyn Widget>> = vec![
+            Box::new(left.text(
+                "a".to_string(),
+                VerticalAlignment::Top,
+                HorizontalAlignment::Left,
+            )),
+            Box::new(right),
+        ];
+
+        for widget in &widgets {
+            widget.render(&mut terminal);
         }
+        assert_eq!(terminal.dump_buffer(), "┌──┐┌──┐\n│a ││  │\n└──┘└──┘\n");
+
+        // The frame reaches the in-memory output in a single write
+        terminal.draw().unwrap();
+        let output = String::from_utf8(terminal.output().clone()).unwrap();
+        assert!(output.starts_with("\x1b[2J"));
+        assert!(output.contains("│a ││  │"));
+
+        // Or into a frame of its own, without any terminal
+        let mut frame = Frame::new(8, 3);
+        widgets[0].render(&mut frame);
+        assert_eq!(frame.dump_buffer(), "┌──┐    \n│a │    \n└──┘    \n");
+    }
 
-        let mut found_newline = false;
-        let line_end = match self.text.iter().position(|c| c == &'\n') {
-            Some(position) => {
-                found_newline = true;
-                position
+    #[test]
+    fn empty_terminal() {
+        let terminal = Terminal::headless(0, 0);
+        assert_eq!(terminal.dump_buffer(), "");
+        assert_eq!(terminal.snapshot(), "---\n");
+
+        assert_eq!(Terminal::headless(0, 3).dump_buffer(), "");
+    }
+
+    #[test]
+    fn text_follow_tail() {
+        let terminal = Terminal::headless(10, 5);
+        let lines = |count: usize| (0..count).map(|line| line.to_string()).collect::<Vec<_>>();
+        let mut text = terminal.area().text(
+            lines(2).join("\n"),
+            VerticalAlignment::Top,
+            HorizontalAlignment::Left,
+        );
+
+        text.set_follow_tail(true);
+        text.change_text(Some(lines(10).join("\n")));
+        assert_eq!(text.scroll_offset(), 7);
+
+        // Stops following but stays at the tail
+        text.set_follow_tail(false);
+        assert_eq!(text.scroll_offset(), 7);
+        text.change_text(Some(lines(20).join("\n")));
+        assert_eq!(text.scroll_offset(), 7);
+
+        text.set_follow_tail(true);
+        assert_eq!(text.scroll_offset(), 17);
+        text.scroll_up(2);
+        assert_eq!(text.scroll_offset(), 15);
+        text.change_text(Some(lines(30).join("\n")));
+        assert_eq!(text.scroll_offset(), 15);
+    }
+
+    #[test]
+    fn item_list_navigation() {
+        let terminal = Terminal::headless(10, 5);
+        let items = (0..10).map(|item| item.to_string()).collect();
+        let mut item_list =
+            terminal
+                .area()
+                .item_list(items, VerticalAlignment::Top, HorizontalAlignment::Left);
+
+        item_list.select_previous();
+        assert_eq!(item_list.selected(), Some(9));
+        assert_eq!(item_list.selection.offset, 7);
+
+        item_list.select_next();
+        assert_eq!(item_list.selected(), Some(9));
+
+        item_list.set_wrap_around(true);
+        item_list.select_next();
+        assert_eq!(item_list.selected(), Some(0));
+        assert_eq!(item_list.selection.offset, 0);
+
+        item_list.select_previous();
+        assert_eq!(item_list.selected(), Some(9));
+
+        item_list.page_up();
+        assert_eq!(item_list.selected(), Some(6));
+        assert_eq!(item_list.selection.offset, 6);
+
+        item_list.page_up();
+        item_list.page_up();
+        assert_eq!(item_list.selected(), Some(0));
+
+        item_list.page_down();
+        assert_eq!(item_list.selected(), Some(3));
+        assert_eq!(item_list.selection.offset, 1);
+
+        item_list.select_last();
+        assert_eq!(item_list.selected(), Some(9));
+        item_list.select_first();
+        assert_eq!(item_list.selected(), Some(0));
+
+        item_list.set_selected(Some(42));
+        assert_eq!(item_list.selected(), Some(9));
+        assert_eq!(item_list.row_at(1, 1), Some(7));
+        assert_eq!(item_list.row_at(1, 3), Some(9));
+    }
+
+    #[test]
+    fn item_list_scrolls_back_when_the_page_grows() {
+        let items: Vec<String> = (0..10).map(|item| item.to_string()).collect();
+
+        let mut terminal = Terminal::headless(10, 6);
+        let mut item_list =
+            terminal
+                .area()
+                .item_list(items, VerticalAlignment::Top, HorizontalAlignment::Left);
+        item_list.set_padding(Spacing::symmetric(2, 0));
+        item_list.set_selected(Some(9));
+        assert_eq!(item_list.selection.offset, 9);
+
+        // The last page is in view instead of the last item followed by empty lines
+        item_list.set_padding(Spacing::ZERO);
+        item_list.set_scrollbar(true);
+        assert_eq!(item_list.selection.offset, 6);
+        item_list.render(&mut terminal);
+        assert_eq!(
+            terminal.dump_buffer(),
+            "┌────────┐\n│6       │\n│7       │\n│8       │\n│9       █\n└────────┘\n"
+        );
+    }
+
+    #[test]
+    fn item_list_narrower_than_its_items() {
+        let items = vec!["a long item".to_string(), "ok".to_string()];
+
+        let mut terminal = Terminal::headless(6, 4);
+        let item_list = terminal.area().item_list(
+            items.clone(),
+            VerticalAlignment::Top,
+            HorizontalAlignment::Left,
+        );
+        item_list.render(&mut terminal);
+        assert_eq!(terminal.dump_buffer(), "┌────┐\n│a l…│\n│ok  │\n└────┘\n");
+
+        let items = vec!["abc".to_string(), "de".to_string()];
+
+        for alignment in [HorizontalAlignment::Right, HorizontalAlignment::Center] {
+            let mut terminal = Terminal::headless(10, 4);
+            let mut item_list =
+                terminal
+                    .area()
+                    .item_list(items.clone(), VerticalAlignment::Top, alignment);
+            // The items fit when the list is created, but not once the padding grows
+            item_list.set_padding(Spacing::symmetric(0, 3));
+            item_list.render(&mut terminal);
+            assert_eq!(
+                terminal.dump_buffer(),
+                "┌────────┐\n│   a…   │\n│   de   │\n└────────┘\n"
+            );
+        }
+    }
+
+    #[test]
+    fn item_list_without_room_for_items() {
+        let items: Vec<String> = (0..10).map(|item| item.to_string()).collect();
+
+        let mut terminal = Terminal::headless(10, 2);
+        let mut item_list = terminal.area().item_list(
+            items.clone(),
+            VerticalAlignment::Top,
+            HorizontalAlignment::Left,
+        );
+        item_list.set_selected(Some(4));
+        item_list.select_next();
+        item_list.page_down();
+        assert_eq!(item_list.selected(), Some(5));
+        assert_eq!(item_list.selection.offset, 5);
+        assert_eq!(item_list.row_at(1, 1), None);
+        item_list.render(&mut terminal);
+        assert_eq!(terminal.dump_buffer(), "┌────────┐\n└────────┘\n");
+
+        let mut terminal = Terminal::headless(10, 6);
+        let mut item_list =
+            terminal
+                .area()
+                .item_list(items, VerticalAlignment::Center, HorizontalAlignment::Right);
+        item_list.set_selected(Some(9));
+        item_list.set_padding(Spacing::uniform(2));
+        item_list.set_scrollbar(true);
+        assert!(item_list.selection.offset <= 9);
+        item_list.render(&mut terminal);
+        assert_eq!(
+            terminal.dump_buffer(),
+            "┌────────┐\n│        │\n│        │\n│        │\n│        │\n└────────┘\n"
+        );
+    }
+
+    #[test]
+    fn table_navigation() {
+        let terminal = Terminal::headless(20, 6);
+        let rows = (0..10)
+            .map(|row| vec![row.to_string(), format!("row {row}")])
+            .collect();
+        let mut table =
+            terminal
+                .area()
+                .table(rows, VerticalAlignment::Top, HorizontalAlignment::Left);
+        table.set_header(Some(vec!["#".to_string(), "Name".to_string()]));
+
+        // The header takes two of the four lines inside the border
+        assert_eq!(table.visible_rows(), 0..2);
+
+        table.select_previous();
+        assert_eq!(table.selected(), Some(9));
+        assert_eq!(table.visible_rows(), 8..10);
+
+        table.page_up();
+        assert_eq!(table.selected(), Some(7));
+        assert_eq!(table.visible_rows(), 7..9);
+
+        table.set_wrap_around(true);
+        table.select_last();
+        table.select_next();
+        assert_eq!(table.selected(), Some(0));
+        assert_eq!(table.row_at(1, 3), Some(0));
+        assert_eq!(table.row_at(1, 4), Some(1));
+        // The header
+        assert_eq!(table.row_at(1, 1), None);
+
+        // Each row takes two lines once wrapped, so only one fits
+        table.set_column_widths(vec![Constraint::Length(1), Constraint::Length(3)]);
+        table.set_cell_wrap_mode(WrapMode::Word);
+        table.page_down();
+        assert_eq!(table.selected(), Some(1));
+        assert_eq!(table.visible_rows(), 1..2);
+    }
+
+    #[test]
+    fn table_scrolls_back_when_the_page_grows() {
+        let rows: Vec<Vec<String>> = (0..10).map(|row| vec![row.to_string()]).collect();
+
+        let mut terminal = Terminal::headless(6, 8);
+        let mut table =
+            terminal
+                .area()
+                .table(rows, VerticalAlignment::Top, HorizontalAlignment::Left);
+        table.set_header(Some(vec!["N".to_string()]));
+        table.set_padding(Spacing::symmetric(1, 0));
+        table.set_selected(Some(9));
+        assert_eq!(table.selection.offset, 8);
+
+        // The rows fill the page instead of the last ones being followed by empty lines
+        table.set_header(None);
+        assert_eq!(table.selection.offset, 6);
+        table.set_padding(Spacing::ZERO);
+        assert_eq!(table.visible_rows(), 4..10);
+        table.render(&mut terminal);
+        assert_eq!(
+            terminal.dump_buffer(),
+            "┌────┐\n│4   │\n│5   │\n│6   │\n│7   │\n│8   │\n│9   │\n└────┘\n"
+        );
+    }
+
+    #[test]
+    fn table_without_room_for_the_header() {
+        let rows = vec![vec!["a".to_string(), "b".to_string()]];
+        let header = Some(vec!["A".to_string(), "B".to_string()]);
+
+        let mut terminal = Terminal::headless(10, 2);
+        let mut table = terminal.area().table(
+            rows.clone(),
+            VerticalAlignment::Top,
+            HorizontalAlignment::Left,
+        );
+        table.set_header(header.clone());
+        table.set_column_separators(true);
+        table.set_selected(Some(0));
+        table.render(&mut terminal);
+        assert_eq!(terminal.dump_buffer(), "┌──┬─────┐\n└──┴─────┘\n");
+
+        // The header fits but the rule below it does not
+        let mut terminal = Terminal::headless(10, 3);
+        let mut table =
+            terminal
+                .area()
+                .table(rows, VerticalAlignment::Bottom, HorizontalAlignment::Left);
+        table.set_header(header);
+        table.set_column_separators(true);
+        table.render(&mut terminal);
+        assert_eq!(
+            terminal.dump_buffer(),
+            "┌──┬─────┐\n│A │ B   │\n└──┴─────┘\n"
+        );
+        assert_eq!(terminal.cell(1, 1).style(), table.header_style);
+    }
+
+    #[test]
+    fn table_horizontal_scrolling() {
+        let terminal = Terminal::headless(10, 4);
+        let rows = vec![vec!["abcdef".to_string(), "ghijkl".to_string()]];
+        let mut table =
+            terminal
+                .area()
+                .table(rows, VerticalAlignment::Top, HorizontalAlignment::Left);
+
+        // The columns are shrunk to fit by default
+        assert_eq!(table.column_lengths, [4, 3]);
+
+        table.set_horizontal_scrolling(true);
+        assert_eq!(table.column_lengths, [6, 6]);
+
+        table.scroll_right(100);
+        assert_eq!(table.horizontal_offset, 5);
+        table.scroll_left(2);
+        assert_eq!(table.horizontal_offset, 3);
+    }
+
+    #[test]
+    fn table_sorting_keeps_the_selected_row() {
+        let terminal = Terminal::headless(20, 6);
+        let rows = [
+            ("init", "0.5"),
+            ("cargo", "12.25"),
+            ("zsh", "2"),
+            ("vim", "2"),
+        ]
+        .iter()
+        .map(|(name, cpu)| vec![name.to_string(), cpu.to_string()])
+        .collect();
+        let mut table =
+            terminal
+                .area()
+                .table(rows, VerticalAlignment::Top, HorizontalAlignment::Left);
+        table.set_selected(Some(1));
+
+        let names = |table: &Table| -> Vec<String> {
+            table.items.iter().map(|row| row[0].clone()).collect()
+        };
+
+        table.sort_by(1, SortDirection::Descending);
+        assert_eq!(names(&table), ["cargo", "zsh", "vim", "init"]);
+        assert_eq!(table.selected(), Some(0));
+
+        table.sort_by(0, SortDirection::Ascending);
+        assert_eq!(names(&table), ["cargo", "init", "vim", "zsh"]);
+        assert_eq!(table.selected(), Some(0));
+        assert_eq!(table.sort(), Some((0, SortDirection::Ascending)));
+
+        // Ties keep their order
+        table.sort_by(1, SortDirection::Ascending);
+        assert_eq!(names(&table), ["init", "vim", "zsh", "cargo"]);
+        assert_eq!(table.selected(), Some(3));
+    }
+
+    #[test]
+    fn split_tiles_the_rectangle() {
+        let mut random = Random::new(0x2545_f491_4f6c_dd1d);
+        let mut below = |bound: usize| random.below(bound);
+
+        for _ in 0..2000 {
+            let (x, y) = (below(10), below(10));
+            let (width, height) = (below(100), below(100));
+
+            let constraints: Vec<Constraint> = (0..1 + below(6))
+                .map(|_| match below(6) {
+                    0 => Constraint::Length(below(50)),
+                    1 => Constraint::Percentage(below(101) as u16),
+                    2 => Constraint::Ratio(below(5) as u32, 1 + below(5) as u32),
+                    3 => Constraint::Min(below(50)),
+                    4 => Constraint::Max(below(50)),
+                    _ => Constraint::Fill(below(4) as u16),
+                })
+                .collect();
+
+            for direction in [Direction::Horizontal, Direction::Vertical] {
+                let mut area = Rectangle::new(None, x, y, width, height);
+                area.set_border_type(BorderType::Rounded);
+                let parts = area.split(direction, &constraints);
+                assert_eq!(parts.len(), constraints.len());
+                assert!(parts
+                    .iter()
+                    .all(|part| part.border_type == BorderType::Rounded));
+
+                // Each part starts where the previous one ends and the last one ends with the area
+                let mut start = match direction {
+                    Direction::Horizontal => x,
+                    Direction::Vertical => y,
+                };
+                for part in &parts {
+                    match direction {
+                        Direction::Horizontal => {
+                            assert_eq!((part.x, part.y, part.height), (start, y, height));
+                            start += part.width;
+                        }
+                        Direction::Vertical => {
+                            assert_eq!((part.x, part.y, part.width), (x, start, width));
+                            start += part.height;
+                        }
+                    }
+                }
+
+                let end = match direction {
+                    Direction::Horizontal => x + width,
+                    Direction::Vertical => y + height,
+                };
+                assert_eq!(start, end, "{constraints:?} do not tile {width}x{height}");
+
+                // With merged borders, neighbours share at most the cell of the wall between them
+                let mut area = Rectangle::new(None, x, y, width, height);
+                area.set_merged_borders(true);
+                let spans: Vec<(usize, usize)> = area
+                    .split(direction, &constraints)
+                    .iter()
+                    .map(|part| match direction {
+                        Direction::Horizontal => (part.x, part.x + part.width),
+                        Direction::Vertical => (part.y, part.y + part.height),
+                    })
+                    .collect();
+
+                let origin = match direction {
+                    Direction::Horizontal => x,
+                    Direction::Vertical => y,
+                };
+                assert_eq!(spans[0].0, origin);
+                assert_eq!(spans[spans.len() - 1].1, end);
+                for pair in spans.windows(2) {
+                    let ((_, previous_end), (next_start, _)) = (pair[0], pair[1]);
+                    assert!(next_start <= previous_end && next_start + 1 >= previous_end);
+                }
             }
-            None => self.text.len(),
+        }
+    }
+
+    #[test]
+    fn merged_borders_next_to_text_and_scrollbars() {
+        let split = |terminal: &Terminal<Vec<u8>>| {
+            let mut area = terminal.area();
+            area.set_merged_borders(true);
+            let mut parts = area.split(
+                Direction::Horizontal,
+                &[Constraint::Length(5), Constraint::Fill(1)],
+            );
+            let right = parts.pop().unwrap();
+            (parts.pop().unwrap(), right)
         };
 
-        // FIXME: Account for word boundaries
+        // Text that looks like a border is not joined to the wall, nor kept over it
+        let mut terminal = Terminal::headless(12, 4);
+        let (left, right) = split(&terminal);
+        let mut text = left.text(
+            "abcd-\nabcd#\nabcd+\nabcd|".to_string(),
+            VerticalAlignment::Top,
+            HorizontalAlignment::Left,
+        );
+        text.set_borders(Borders::NONE);
+        text.render(&mut terminal);
+        right.render(&mut terminal);
+        assert_eq!(
+            terminal.dump_buffer(),
+            "abcd┌──────┐\nabcd│      │\nabcd│      │\nabcd└──────┘\n"
+        );
+
+        // While the thumb of a scrollbar on the shared wall is
+        let mut terminal = Terminal::headless(12, 4);
+        let (left, right) = split(&terminal);
+        let items = (0..10).map(|item| item.to_string()).collect();
+        let mut item_list =
+            left.item_list(items, VerticalAlignment::Top, HorizontalAlignment::Left);
+        item_list.set_scrollbar(true);
+        item_list.render(&mut terminal);
+        right.render(&mut terminal);
+        assert_eq!(terminal.cell(4, 1).symbol(), "█");
+        assert_eq!(terminal.cell(4, 2).symbol(), "│");
+
+        // Until the next frame
+        terminal.draw().unwrap();
+        right.render(&mut terminal);
+        assert_eq!(terminal.cell(4, 1).symbol(), "│");
+    }
+
+    #[test]
+    fn inner_area() {
+        let bounds = |area: Rectangle| (area.x, area.y, area.width, area.height);
+
+        let mut area = Rectangle::new(None, 2, 1, 20, 10);
+        assert_eq!(bounds(area.inner()), (3, 2, 18, 8));
 
-        // We do not want to print the '\n' but we do want to remove it from the buffer so we can
-        // parse the next line later, otherwise it gets stuck
-        let strip_newline = found_newline & (line_end <= self.width);
-        let hardwrapped_line_end = usize::min(self.width, line_end);
+        area.set_borders(Borders::TOP | Borders::LEFT);
+        assert_eq!(bounds(area.inner()), (3, 2, 19, 9));
 
-        let result = &self.text[0..hardwrapped_line_end];
-        self.text = &self.text[hardwrapped_line_end + strip_newline as usize..];
+        area.set_borders(Borders::NONE);
+        assert_eq!(bounds(area.inner()), (2, 1, 20, 10));
 
-        Some(result)
+        area.set_borders(Borders::ALL);
+        area.set_padding(Spacing::symmetric(1, 2));
+        area.set_margin(Spacing {
+            left: 3,
+            ..Spacing::ZERO
+        });
+        assert_eq!(bounds(area.inner()), (8, 3, 11, 6));
+
+        // Nothing is left, but it does not underflow
+        area.set_padding(Spacing::uniform(20));
+        assert_eq!(bounds(area.inner()).2, 0);
+        assert_eq!(bounds(area.inner()).3, 0);
     }
-}
 
-// TODO: Add tests with expectations
+    #[test]
+    fn headless_terminal_renders_into_the_buffer() {
+        let mut terminal = Terminal::headless(6, 3);
+        let mut area = terminal.area();
+        area.set_title(Some("ab".into()));
+        area.set_border_color(Color::Green);
+        area.render(&mut terminal);
+
+        assert_eq!(terminal.dump_buffer(), "┌─ab─┐\n│    │\n└────┘\n");
+        assert_eq!(terminal.cell(0, 0).symbol(), "┌");
+        assert_eq!(terminal.cell(0, 0).foreground_color(), Color::Green);
+        assert_eq!(terminal.cell(1, 1).symbol(), " ");
+        assert_eq!(terminal.cell(1, 1).foreground_color(), Color::Default);
+
+        terminal.draw().unwrap();
+
+        assert!(terminal.output().starts_with(b"\x1b[2J\x1b[1;1H"));
+        assert_eq!(terminal.dump_buffer(), "      \n      \n      \n");
+    }
+}
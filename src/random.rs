@@ -0,0 +1,21 @@
+/// A xorshift generator for the tests, we only need reproducible noise.
+pub(crate) struct Random(u64);
+
+impl Random {
+    /// The seed must not be zero, or every number is zero.
+    pub(crate) fn new(seed: u64) -> Random {
+        Random(seed)
+    }
+
+    pub(crate) fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    /// A number from 0 up to, but not including, `bound`.
+    pub(crate) fn below(&mut self, bound: usize) -> usize {
+        (self.next() % bound as u64) as usize
+    }
+}
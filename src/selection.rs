@@ -0,0 +1,107 @@
+use crate::{Color, Style};
+
+/// The selected row of a list and the rows in view, shared by `ItemList` and `Table`. The widget
+/// owns the rows, so it tells how many there are on each call.
+pub(crate) struct Selection {
+    pub(crate) selected: Option<usize>,
+    // The index of the first row in view
+    pub(crate) offset: usize,
+    pub(crate) wrap_around: bool,
+    pub(crate) highlight_style: Style,
+}
+
+impl Selection {
+    pub(crate) fn new() -> Selection {
+        Selection {
+            selected: None,
+            offset: 0,
+            wrap_around: false,
+            highlight_style: Style::default()
+                .foreground(Color::Black)
+                .background(Color::Cyan),
+        }
+    }
+
+    /// Selects the row, or the last one if it's past the end. Nothing is selected without rows.
+    pub(crate) fn select(&mut self, index: Option<usize>, len: usize) {
+        self.selected = index
+            .filter(|_| len > 0)
+            .map(|index| usize::min(index, len - 1));
+    }
+
+    pub(crate) fn select_next(&mut self, len: usize) {
+        let next = match self.selected {
+            None => 0,
+            Some(selected) if selected + 1 < len => selected + 1,
+            Some(_) if self.wrap_around => 0,
+            Some(selected) => selected,
+        };
+
+        self.select(Some(next), len);
+    }
+
+    pub(crate) fn select_previous(&mut self, len: usize) {
+        let previous = match self.selected {
+            None => len.saturating_sub(1),
+            Some(selected) if selected > 0 => selected - 1,
+            Some(_) if self.wrap_around => len.saturating_sub(1),
+            Some(selected) => selected,
+        };
+
+        self.select(Some(previous), len);
+    }
+
+    pub(crate) fn page_up(&mut self, rows: usize, len: usize) {
+        let selected = self.selected.unwrap_or(0);
+        self.select(Some(selected.saturating_sub(rows)), len);
+    }
+
+    pub(crate) fn page_down(&mut self, rows: usize, len: usize) {
+        let selected = self.selected.map_or(0, |selected| selected + rows);
+        self.select(Some(selected), len);
+    }
+
+    /// The offset that brings the selected row into view, where there are `page_height` lines for
+    /// `len` rows and each of them takes `row_height` lines. The rows are never scrolled so far
+    /// that there is room left after the last one, as when the page grows.
+    pub(crate) fn scrolled_offset(
+        &self,
+        len: usize,
+        page_height: usize,
+        row_height: impl Fn(usize) -> usize,
+    ) -> usize {
+        // The first row of the last page, the last row is in view even if it does not fit
+        let mut last_page = len;
+        let mut last_page_height = 0;
+        while last_page > 0 {
+            let height = row_height(last_page - 1);
+            if last_page_height + height > page_height {
+                break;
+            }
+
+            last_page_height += height;
+            last_page -= 1;
+        }
+        let offset = self.offset.min(last_page).min(len.saturating_sub(1));
+
+        let Some(selected) = self.selected else {
+            return offset;
+        };
+
+        if selected < offset {
+            return selected;
+        }
+
+        // The rows from the offset to the selected one must fit. Without room for any row the
+        // selected one still comes first, never before the offset
+        let mut offset = offset;
+        let mut height: usize = (offset..=selected).map(&row_height).sum();
+
+        while offset < selected && height > page_height {
+            height -= row_height(offset);
+            offset += 1;
+        }
+
+        offset
+    }
+}
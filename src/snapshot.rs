@@ -0,0 +1,134 @@
+//! Snapshot testing of rendered frames.
+//!
+//! A snapshot is a plain text description of a frame: the characters, followed by an overlay
+//! where every cell is replaced by a letter identifying its style, and the legend of those
+//! letters. Cells with the default style are shown as '.' in the overlay to keep it readable.
+//!
+//! Snapshots are stored in files and compared with `assert_frame!`. Running the tests with
+//! `TINYTUI_UPDATE_SNAPSHOTS=1` writes the current frames to the files instead of comparing them.
+
+use std::io::Write;
+use std::path::Path;
+
+use crate::{Cell, Style, Terminal};
+
+const UPDATE_SNAPSHOTS_VARIABLE: &str = "TINYTUI_UPDATE_SNAPSHOTS";
+
+/// Compares the frame being rendered in the terminal with the snapshot stored in
+/// 'tests/snapshots/<name>.snap' of the crate calling it.
+#[macro_export]
+macro_rules! assert_frame {
+    ($terminal:expr, $name:expr) => {
+        $crate::assert_snapshot(
+            &$terminal.snapshot(),
+            &::std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+                .join("tests")
+                .join("snapshots")
+                .join(format!("{}.snap", $name)),
+        )
+    };
+}
+
+impl<W: Write> Terminal<W> {
+    /// Describes the frame being rendered, characters and styles, as plain text.
+    pub fn snapshot(&self) -> String {
+        let mut styles: Vec<Style> = Vec::new();
+        let mut overlay = String::new();
+
+        // `chunks` can't split the buffer in lines without columns
+        for line in self.frame.buffer.chunks(self.frame.width.max(1)) {
+            for cell in line {
+                overlay.push(Self::style_symbol(cell, &mut styles));
+            }
+            overlay.push('\n');
+        }
+
+        let mut snapshot = self.dump_buffer();
+        snapshot.push_str("---\n");
+        snapshot.push_str(&overlay);
+
+        if !styles.is_empty() {
+            snapshot.push_str("---\n");
+        }
+
+        for (i, style) in styles.iter().enumerate() {
+            snapshot.push_str(&format!(
+                "{}: foreground={:?} background={:?}",
+                Self::legend_letter(i),
+                style.foreground_color,
+                style.background_color
+            ));
+
+            if !style.modifiers.is_empty() {
+                snapshot.push_str(&format!(" modifiers={:?}", style.modifiers));
+            }
+
+            snapshot.push('\n');
+        }
+
+        snapshot
+    }
+
+    fn style_symbol(cell: &Cell, styles: &mut Vec<Style>) -> char {
+        let style = cell.style();
+
+        if style == Style::default() {
+            return '.';
+        }
+
+        let index = match styles.iter().position(|known| *known == style) {
+            Some(index) => index,
+            None => {
+                styles.push(style);
+                styles.len() - 1
+            }
+        };
+
+        Self::legend_letter(index)
+    }
+
+    fn legend_letter(index: usize) -> char {
+        const LETTERS: &[u8] = b"abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ";
+
+        LETTERS.get(index).map_or('?', |letter| *letter as char)
+    }
+}
+
+/// Compares `actual` with the snapshot stored at `path`, panicking with both when they differ.
+/// If `TINYTUI_UPDATE_SNAPSHOTS` is set, the snapshot is overwritten instead.
+pub fn assert_snapshot(actual: &str, path: &Path) {
+    let update = std::env::var_os(UPDATE_SNAPSHOTS_VARIABLE).is_some_and(|value| value != "0");
+
+    if update {
+        if let Some(directory) = path.parent() {
+            std::fs::create_dir_all(directory).unwrap();
+        }
+        std::fs::write(path, actual).unwrap();
+        return;
+    }
+
+    let expected = match std::fs::read_to_string(path) {
+        Ok(expected) => expected,
+        Err(err) => panic!(
+            "could not read the snapshot '{}': {err}\n\
+            run the tests with {UPDATE_SNAPSHOTS_VARIABLE}=1 to create it, the frame was:\n{actual}",
+            path.display()
+        ),
+    };
+
+    if expected != actual {
+        let line = expected
+            .lines()
+            .zip(actual.lines())
+            .position(|(expected, actual)| expected != actual)
+            .unwrap_or_else(|| usize::min(expected.lines().count(), actual.lines().count()));
+
+        panic!(
+            "the frame does not match the snapshot '{}', the first difference is at line {}\n\
+            run the tests with {UPDATE_SNAPSHOTS_VARIABLE}=1 to accept it\n\
+            expected:\n{expected}\nactual:\n{actual}",
+            path.display(),
+            line + 1,
+        );
+    }
+}
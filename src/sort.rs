@@ -0,0 +1,272 @@
+use std::cmp::Ordering;
+
+/// The order of the rows of a sorted `Table`.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum SortDirection {
+    /// The smallest value first.
+    Ascending,
+    /// The largest value first.
+    Descending,
+}
+
+impl SortDirection {
+    /// The glyph shown next to the header of the sorted column.
+    pub(crate) fn indicator(self) -> &'static str {
+        match self {
+            SortDirection::Ascending => "▲",
+            SortDirection::Descending => "▼",
+        }
+    }
+}
+
+/// Compares the text the way people expect: numbers by their value, so "9" comes before "10" and
+/// "0.5" before "12.25", and the rest ignoring the case. Ties are broken by the text itself so the
+/// order is total.
+pub(crate) fn natural_cmp(a: &str, b: &str) -> Ordering {
+    let mut a_chunks = Chunks::new(a.trim());
+    let mut b_chunks = Chunks::new(b.trim());
+
+    loop {
+        let ordering = match (a_chunks.next(), b_chunks.next()) {
+            (None, None) => return a.cmp(b),
+            (None, Some(_)) => Ordering::Less,
+            (Some(_), None) => Ordering::Greater,
+            (Some(a_chunk), Some(b_chunk)) => compare_chunks(a_chunk, b_chunk),
+        };
+
+        if ordering != Ordering::Equal {
+            return ordering;
+        }
+    }
+}
+
+fn compare_chunks(a: Chunk, b: Chunk) -> Ordering {
+    match (a, b) {
+        (Chunk::Number(a), Chunk::Number(b)) => compare_numbers(a, b),
+        // Numbers come before words, e.g. "2 apples" before "apples"
+        (Chunk::Number(_), Chunk::Text(_)) => Ordering::Less,
+        (Chunk::Text(_), Chunk::Number(_)) => Ordering::Greater,
+        (Chunk::Text(a), Chunk::Text(b)) => a
+            .chars()
+            .flat_map(char::to_lowercase)
+            .cmp(b.chars().flat_map(char::to_lowercase)),
+    }
+}
+
+fn compare_numbers(a: Number, b: Number) -> Ordering {
+    // Any number of digits, so no parsing. Without the leading zeros the longest integer part is
+    // the largest, and without the trailing zeros the fractional parts compare as text.
+    let magnitude = a
+        .integer
+        .len()
+        .cmp(&b.integer.len())
+        .then_with(|| a.integer.cmp(b.integer))
+        .then_with(|| a.fraction.cmp(b.fraction));
+
+    match (a.is_negative(), b.is_negative()) {
+        (true, false) => Ordering::Less,
+        (false, true) => Ordering::Greater,
+        (true, true) => magnitude.reverse(),
+        (false, false) => magnitude,
+    }
+}
+
+#[derive(Copy, Clone, Debug)]
+enum Chunk<'a> {
+    Number(Number<'a>),
+    Text(&'a str),
+}
+
+#[derive(Copy, Clone, Debug)]
+struct Number<'a> {
+    negative: bool,
+    // The digits before the decimal point, without the leading zeros
+    integer: &'a str,
+    // The digits after the decimal point, without the trailing zeros
+    fraction: &'a str,
+}
+
+impl Number<'_> {
+    // "-0" is just zero
+    fn is_negative(&self) -> bool {
+        self.negative && !(self.integer.is_empty() && self.fraction.is_empty())
+    }
+}
+
+/// Splits the text in numbers, e.g. "42" or "-0.5", and runs of everything else. A minus sign is
+/// only part of the number at the start of the text, elsewhere it's most likely a separator, e.g.
+/// in "id-42".
+struct Chunks<'a> {
+    text: &'a str,
+    at_start: bool,
+}
+
+impl<'a> Chunks<'a> {
+    fn new(text: &'a str) -> Chunks<'a> {
+        Chunks {
+            text,
+            at_start: true,
+        }
+    }
+}
+
+impl<'a> Iterator for Chunks<'a> {
+    type Item = Chunk<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.text.is_empty() {
+            return None;
+        }
+
+        let digits_length = |text: &str| {
+            text.find(|c: char| !c.is_ascii_digit())
+                .unwrap_or(text.len())
+        };
+
+        let negative =
+            self.at_start && self.text.starts_with('-') && digits_length(&self.text[1..]) > 0;
+        self.at_start = false;
+
+        let sign_length = negative as usize;
+        let integer_length = digits_length(&self.text[sign_length..]);
+
+        if integer_length == 0 {
+            let end = self
+                .text
+                .find(|c: char| c.is_ascii_digit())
+                .unwrap_or(self.text.len());
+            let (text, rest) = self.text.split_at(end);
+            self.text = rest;
+
+            return Some(Chunk::Text(text));
+        }
+
+        let mut end = sign_length + integer_length;
+        let integer = &self.text[sign_length..end];
+
+        // A decimal point only counts with digits after it, e.g. not in "10.txt"
+        let mut fraction = "";
+        if let Some(after_point) = self.text[end..].strip_prefix('.') {
+            let fraction_length = digits_length(after_point);
+            if fraction_length > 0 {
+                fraction = &after_point[..fraction_length];
+                end += 1 + fraction_length;
+            }
+        }
+
+        self.text = &self.text[end..];
+
+        Some(Chunk::Number(Number {
+            negative,
+            integer: integer.trim_start_matches('0'),
+            fraction: fraction.trim_end_matches('0'),
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sorted(items: &[&str]) -> Vec<String> {
+        let mut items: Vec<String> = items.iter().map(|item| item.to_string()).collect();
+        items.sort_by(|a, b| natural_cmp(a, b));
+        items
+    }
+
+    #[test]
+    fn natural_order() {
+        assert_eq!(sorted(&["10", "9", "100", "1"]), ["1", "9", "10", "100"]);
+        assert_eq!(
+            sorted(&["12.25", "0.5", "-3", "2"]),
+            ["-3", "0.5", "2", "12.25"]
+        );
+        assert_eq!(
+            sorted(&["file10.txt", "file2.txt", "File1.txt"]),
+            ["File1.txt", "file2.txt", "file10.txt"]
+        );
+        assert_eq!(sorted(&["b", "B", "a"]), ["a", "B", "b"]);
+        assert_eq!(sorted(&["007", "7", "08"]), ["007", "7", "08"]);
+        assert_eq!(sorted(&["abc", "", "2 apples"]), ["", "2 apples", "abc"]);
+        // Too long to be parsed by anything but still compared by value
+        assert_eq!(
+            sorted(&["id-100000000000000000000001", "id-99999999999999999999999"]),
+            ["id-99999999999999999999999", "id-100000000000000000000001"]
+        );
+        assert_eq!(
+            sorted(&["-0.25", "-1.5", "-0", "0.250", "1.10", "1.9"]),
+            ["-1.5", "-0.25", "-0", "0.250", "1.10", "1.9"]
+        );
+        // Only digits make numbers
+        assert_eq!(
+            sorted(&["inf", "2", "nan", "1e5"]),
+            ["1e5", "2", "inf", "nan"]
+        );
+    }
+
+    #[test]
+    fn natural_order_is_total() {
+        let items = [
+            "1.10",
+            "1.9",
+            "1.9x",
+            "1.9.1",
+            "1.",
+            ".5",
+            "-3",
+            "-0",
+            "0",
+            "-",
+            "-x",
+            "007",
+            "7",
+            "08",
+            "12.25",
+            "0.5",
+            "0.50",
+            "inf",
+            "nan",
+            "1e5",
+            "file2.txt",
+            "file10.txt",
+            "File1.txt",
+            "id-9",
+            "id-10",
+            "",
+            " 2",
+            "2 apples",
+            "abc",
+            "ABC",
+            "a1b",
+            "a01b",
+        ];
+
+        for a in items {
+            assert_eq!(natural_cmp(a, a), Ordering::Equal);
+
+            for b in items {
+                assert_eq!(
+                    natural_cmp(a, b),
+                    natural_cmp(b, a).reverse(),
+                    "{a:?} {b:?}"
+                );
+
+                for c in items {
+                    if natural_cmp(a, b).is_le() && natural_cmp(b, c).is_le() {
+                        assert!(natural_cmp(a, c).is_le(), "{a:?} {b:?} {c:?}");
+                    }
+                }
+            }
+        }
+
+        // So the order they come in does not matter
+        let expected = sorted(&items);
+        let mut items = items.to_vec();
+        for _ in 0..items.len() {
+            items.rotate_left(1);
+            items.reverse();
+            assert_eq!(sorted(&items), expected);
+        }
+        assert_eq!(sorted(&["1.9x", "1.9", "1.10"]), ["1.10", "1.9", "1.9x"]);
+    }
+}
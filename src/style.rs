@@ -0,0 +1,275 @@
+use std::io::Write;
+use std::ops::{BitOr, BitOrAssign};
+
+use crate::{Color, ColorSupport};
+
+/// Text attributes, they can be combined with `|`.
+#[derive(Copy, Clone, PartialEq, Eq, Default)]
+pub struct Modifier(u8);
+
+impl Modifier {
+    pub const NONE: Modifier = Modifier(0);
+    pub const BOLD: Modifier = Modifier(1);
+    pub const DIM: Modifier = Modifier(1 << 1);
+    pub const ITALIC: Modifier = Modifier(1 << 2);
+    pub const UNDERLINE: Modifier = Modifier(1 << 3);
+    pub const REVERSE: Modifier = Modifier(1 << 4);
+    pub const STRIKETHROUGH: Modifier = Modifier(1 << 5);
+
+    // Each modifier with the SGR parameters that turn it on and off. Bold and dim share the same
+    // parameter to be turned off.
+    const PARAMETERS: [(Modifier, &'static str, &'static str); 6] = [
+        (Modifier::BOLD, "1", "22"),
+        (Modifier::DIM, "2", "22"),
+        (Modifier::ITALIC, "3", "23"),
+        (Modifier::UNDERLINE, "4", "24"),
+        (Modifier::REVERSE, "7", "27"),
+        (Modifier::STRIKETHROUGH, "9", "29"),
+    ];
+
+    pub fn contains(&self, other: Modifier) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+
+    pub fn insert(&mut self, other: Modifier) {
+        self.0 |= other.0
+    }
+
+    pub fn remove(&mut self, other: Modifier) {
+        self.0 &= !other.0
+    }
+
+    fn difference(self, other: Modifier) -> Modifier {
+        Modifier(self.0 & !other.0)
+    }
+}
+
+impl std::fmt::Debug for Modifier {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        const NAMES: [(Modifier, &str); 6] = [
+            (Modifier::BOLD, "BOLD"),
+            (Modifier::DIM, "DIM"),
+            (Modifier::ITALIC, "ITALIC"),
+            (Modifier::UNDERLINE, "UNDERLINE"),
+            (Modifier::REVERSE, "REVERSE"),
+            (Modifier::STRIKETHROUGH, "STRIKETHROUGH"),
+        ];
+
+        if self.is_empty() {
+            return write!(f, "NONE");
+        }
+
+        let names: Vec<&str> = NAMES
+            .iter()
+            .filter(|(modifier, _)| self.contains(*modifier))
+            .map(|(_, name)| *name)
+            .collect();
+
+        write!(f, "{}", names.join(" | "))
+    }
+}
+
+impl BitOr for Modifier {
+    type Output = Modifier;
+
+    fn bitor(self, rhs: Modifier) -> Modifier {
+        Modifier(self.0 | rhs.0)
+    }
+}
+
+impl BitOrAssign for Modifier {
+    fn bitor_assign(&mut self, rhs: Modifier) {
+        self.0 |= rhs.0
+    }
+}
+
+/// How a cell looks like: its colors and text attributes.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct Style {
+    pub foreground_color: Color,
+    pub background_color: Color,
+    pub modifiers: Modifier,
+}
+
+impl Default for Style {
+    fn default() -> Self {
+        Style {
+            foreground_color: Color::Default,
+            background_color: Color::Default,
+            modifiers: Modifier::NONE,
+        }
+    }
+}
+
+impl Style {
+    pub fn foreground(self, color: Color) -> Style {
+        Style {
+            foreground_color: color,
+            ..self
+        }
+    }
+
+    pub fn background(self, color: Color) -> Style {
+        Style {
+            background_color: color,
+            ..self
+        }
+    }
+
+    pub fn modifiers(self, modifiers: Modifier) -> Style {
+        Style { modifiers, ..self }
+    }
+
+    /// Writes the shortest SGR sequence we know of that changes the terminal from the `from` style
+    /// to the `to` one. When `from` is unknown the attributes are reset first.
+    pub(crate) fn write_transition(
+        from: Option<Style>,
+        to: Style,
+        color_support: ColorSupport,
+        output: &mut impl Write,
+    ) -> std::io::Result<()> {
+        let reset = Style::reset_parameters(to, color_support);
+
+        let parameters = match from {
+            None => reset,
+            Some(from) => {
+                let incremental = Style::incremental_parameters(from, to, color_support);
+
+                if incremental.join(";").len() <= reset.join(";").len() {
+                    incremental
+                } else {
+                    reset
+                }
+            }
+        };
+
+        if parameters.is_empty() {
+            return Ok(());
+        }
+
+        write!(output, "\x1b[{}m", parameters.join(";"))
+    }
+
+    /// Turns off only the modifiers that are not used anymore and sets what changed.
+    fn incremental_parameters(from: Style, to: Style, color_support: ColorSupport) -> Vec<String> {
+        let mut parameters = Vec::new();
+
+        let removed = from.modifiers.difference(to.modifiers);
+        let mut added = to.modifiers.difference(from.modifiers);
+
+        for (modifier, _, off) in Modifier::PARAMETERS {
+            if removed.contains(modifier) && !parameters.contains(&off.to_string()) {
+                parameters.push(off.to_string());
+            }
+        }
+
+        // Turning off bold also turns off dim and vice versa, so they may need to be set again
+        if removed.contains(Modifier::BOLD) || removed.contains(Modifier::DIM) {
+            added |= Modifier(to.modifiers.0 & (Modifier::BOLD.0 | Modifier::DIM.0));
+        }
+
+        for (modifier, on, _) in Modifier::PARAMETERS {
+            if added.contains(modifier) {
+                parameters.push(on.to_string());
+            }
+        }
+
+        if from.foreground_color != to.foreground_color {
+            parameters.push(to.foreground_color.foreground_parameter(color_support));
+        }
+
+        if from.background_color != to.background_color {
+            parameters.push(to.background_color.background_parameter(color_support));
+        }
+
+        parameters
+    }
+
+    /// Resets everything and sets the style from scratch.
+    fn reset_parameters(to: Style, color_support: ColorSupport) -> Vec<String> {
+        let mut parameters = vec!["0".to_string()];
+
+        for (modifier, on, _) in Modifier::PARAMETERS {
+            if to.modifiers.contains(modifier) {
+                parameters.push(on.to_string());
+            }
+        }
+
+        if to.foreground_color != Color::Default {
+            parameters.push(to.foreground_color.foreground_parameter(color_support));
+        }
+
+        if to.background_color != Color::Default {
+            parameters.push(to.background_color.background_parameter(color_support));
+        }
+
+        parameters
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn transition(from: Option<Style>, to: Style) -> String {
+        let mut output = Vec::new();
+        Style::write_transition(from, to, ColorSupport::TrueColor, &mut output).unwrap();
+        String::from_utf8(output).unwrap()
+    }
+
+    #[test]
+    fn transitions() {
+        let default = Style::default();
+        let bold = default.modifiers(Modifier::BOLD);
+        let bold_dim = default.modifiers(Modifier::BOLD | Modifier::DIM);
+        let green = default.foreground(Color::Green);
+
+        assert_eq!(transition(None, default), "\x1b[0m");
+        assert_eq!(
+            transition(None, bold.foreground(Color::Red)),
+            "\x1b[0;1;31m"
+        );
+        assert_eq!(transition(Some(default), default), "");
+        assert_eq!(transition(Some(default), bold), "\x1b[1m");
+        assert_eq!(transition(Some(bold), default), "\x1b[0m");
+        assert_eq!(transition(Some(bold_dim), bold), "\x1b[0;1m");
+        assert_eq!(transition(Some(green), default), "\x1b[0m");
+        assert_eq!(
+            transition(Some(bold.foreground(Color::Green)), green),
+            "\x1b[22m"
+        );
+        assert_eq!(
+            transition(
+                Some(default),
+                default
+                    .background(Color::Cyan)
+                    .modifiers(Modifier::ITALIC | Modifier::UNDERLINE)
+            ),
+            "\x1b[3;4;46m"
+        );
+        // A reset is shorter than turning off every modifier one by one
+        assert_eq!(
+            transition(
+                Some(default.modifiers(
+                    Modifier::ITALIC
+                        | Modifier::UNDERLINE
+                        | Modifier::REVERSE
+                        | Modifier::STRIKETHROUGH
+                )),
+                default
+            ),
+            "\x1b[0m"
+        );
+        assert_eq!(
+            transition(
+                Some(green.modifiers(Modifier::ITALIC | Modifier::STRIKETHROUGH)),
+                green.modifiers(Modifier::UNDERLINE)
+            ),
+            "\x1b[0;4;32m"
+        );
+    }
+}
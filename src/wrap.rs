@@ -0,0 +1,348 @@
+use std::borrow::Cow;
+
+use crate::width::{self, Graphemes};
+
+/// How text that does not fit the width is laid out.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum WrapMode {
+    /// Breaks the line exactly at the width, even in the middle of a word.
+    Hard,
+    /// Breaks the line at the last whitespace that fits, words longer than the whole width are
+    /// broken as in `Hard`.
+    Word,
+    /// Cuts what does not fit.
+    Truncate,
+    /// Cuts what does not fit, ending the line with '…' to show that something is missing.
+    Ellipsis,
+}
+
+/// Splits the text in the lines we draw, none of them wider than `width`. Explicit line breaks are
+/// always honored.
+pub(crate) struct WrappedLines<'a> {
+    text: &'a str,
+    width: usize,
+    wrap_mode: WrapMode,
+}
+
+impl<'a> WrappedLines<'a> {
+    pub(crate) fn new(text: &'a str, width: usize, wrap_mode: WrapMode) -> Self {
+        Self {
+            text,
+            width,
+            wrap_mode,
+        }
+    }
+
+    fn next_truncated(&mut self, ellipsis: bool) -> Cow<'a, str> {
+        let (line, rest) = self.text.split_once('\n').unwrap_or((self.text, ""));
+        self.text = rest;
+
+        truncate(line, self.width, ellipsis)
+    }
+
+    fn next_wrapped(&mut self, word_wrap: bool) -> Option<&'a str> {
+        // A cluster wider than the whole line would never fit, it's dropped so we do not get stuck
+        // on it
+        while let Some(grapheme) = Graphemes::new(self.text).next() {
+            if width::grapheme_width(grapheme) <= self.width {
+                break;
+            }
+            self.text = &self.text[grapheme.len()..];
+        }
+
+        if self.text.is_empty() {
+            return None;
+        }
+
+        let text = self.text;
+        let mut line_end = 0;
+        let mut line_width = 0;
+        // Where the line ends and the next one starts if we break it at the last whitespace
+        let mut word_break = None;
+
+        for grapheme in Graphemes::new(text) {
+            if grapheme == "\n" {
+                // We do not want to print the '\n' but we do want to remove it from the buffer so
+                // we can parse the next line later, otherwise it gets stuck
+                self.text = &text[line_end + 1..];
+                return Some(&text[..line_end]);
+            }
+
+            let grapheme_width = width::grapheme_width(grapheme);
+            let whitespace = is_whitespace(grapheme);
+
+            if line_width + grapheme_width > self.width {
+                // The indentation does not leave room for the first word, so it's dropped
+                let unindented = text.trim_start_matches([' ', '\t']);
+                if word_wrap && !whitespace && word_break.is_none() && unindented.len() < text.len()
+                {
+                    self.text = unindented;
+                    return self.next_wrapped(word_wrap);
+                }
+
+                let (end, next_start) = match word_break {
+                    _ if word_wrap && whitespace => (text[..line_end].trim_end().len(), line_end),
+                    Some(word_break) if word_wrap => word_break,
+                    _ => (line_end, line_end),
+                };
+
+                self.text = &text[next_start..];
+                if word_wrap {
+                    self.skip_whitespace();
+                }
+
+                return Some(&text[..end]);
+            }
+
+            if whitespace && !text[..line_end].trim().is_empty() {
+                word_break = Some((text[..line_end].trim_end().len(), line_end));
+            }
+
+            line_end += grapheme.len();
+            line_width += grapheme_width;
+        }
+
+        self.text = "";
+        Some(text)
+    }
+
+    // The whitespace where we broke a line is not carried to the next one. If it's all that was
+    // left before a line break, the line break is consumed as well, otherwise we would get an
+    // empty line that is not in the text.
+    fn skip_whitespace(&mut self) {
+        let rest = self.text.trim_start_matches([' ', '\t']);
+
+        self.text = rest.strip_prefix('\n').unwrap_or(rest);
+    }
+}
+
+impl<'a> Iterator for WrappedLines<'a> {
+    type Item = Cow<'a, str>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.text.is_empty() {
+            return None;
+        }
+
+        match self.wrap_mode {
+            WrapMode::Hard => self.next_wrapped(false).map(Cow::Borrowed),
+            WrapMode::Word => self.next_wrapped(true).map(Cow::Borrowed),
+            WrapMode::Truncate => Some(self.next_truncated(false)),
+            WrapMode::Ellipsis => Some(self.next_truncated(true)),
+        }
+    }
+}
+
+fn is_whitespace(grapheme: &str) -> bool {
+    grapheme == " " || grapheme == "\t"
+}
+
+/// Tab stops are this many columns apart.
+const TAB_WIDTH: usize = 4;
+
+/// Replaces each tab by the spaces up to the next tab stop, tabs have no width of their own and
+/// would otherwise vanish from the screen.
+pub(crate) fn expand_tabs(text: String) -> String {
+    if !text.contains('\t') {
+        return text;
+    }
+
+    let mut expanded = String::with_capacity(text.len());
+    let mut column = 0;
+    for grapheme in Graphemes::new(&text) {
+        match grapheme {
+            "\t" => {
+                let spaces = TAB_WIDTH - column % TAB_WIDTH;
+                expanded.extend(std::iter::repeat_n(' ', spaces));
+                column += spaces;
+                continue;
+            }
+            "\n" => column = 0,
+            _ => column += width::grapheme_width(grapheme),
+        }
+
+        expanded.push_str(grapheme);
+    }
+
+    expanded
+}
+
+/// Cuts the line to fit in `width` columns, replacing the last one by '…' if `ellipsis` is set.
+pub(crate) fn truncate(line: &str, width: usize, ellipsis: bool) -> Cow<'_, str> {
+    if width::str_width(line) <= width {
+        return Cow::Borrowed(line);
+    }
+
+    let available_width = if ellipsis {
+        width.saturating_sub(1)
+    } else {
+        width
+    };
+
+    let mut end = 0;
+    let mut used_width = 0;
+    for grapheme in Graphemes::new(line) {
+        let grapheme_width = width::grapheme_width(grapheme);
+        if used_width + grapheme_width > available_width {
+            break;
+        }
+
+        end += grapheme.len();
+        used_width += grapheme_width;
+    }
+
+    if ellipsis && width > 0 {
+        Cow::Owned(format!("{}…", &line[..end]))
+    } else {
+        Cow::Borrowed(&line[..end])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::random::Random;
+
+    fn lines(text: &str, width: usize, wrap_mode: WrapMode) -> Vec<String> {
+        WrappedLines::new(text, width, wrap_mode)
+            .map(String::from)
+            .collect()
+    }
+
+    #[test]
+    fn hard_wrap() {
+        let hard = |text, width| lines(text, width, WrapMode::Hard);
+
+        assert_eq!(hard("abcdef", 4), ["abcd", "ef"]);
+        assert_eq!(hard("ab\ncd", 2), ["ab", "cd"]);
+        assert_eq!(hard("ab\n\ncd\n", 4), ["ab", "", "cd"]);
+        assert_eq!(hard("日本語です", 4), ["日本", "語で", "す"]);
+        assert_eq!(hard("a日本", 4), ["a日", "本"]);
+        assert_eq!(hard("cafe\u{0301}s", 4), ["cafe\u{0301}", "s"]);
+        // A character wider than the line does not get the wrapping stuck
+        assert_eq!(hard("日a", 1), ["a"]);
+    }
+
+    #[test]
+    fn word_wrap() {
+        let word = |text, width| lines(text, width, WrapMode::Word);
+
+        assert_eq!(word("the quick brown fox", 10), ["the quick", "brown fox"]);
+        assert_eq!(word("the quick brown fox", 9), ["the quick", "brown fox"]);
+        assert_eq!(word("the   quick", 5), ["the", "quick"]);
+        assert_eq!(word("a verylongword b", 5), ["a", "veryl", "ongwo", "rd b"]);
+        assert_eq!(word("  indented text", 10), ["  indented", "text"]);
+        assert_eq!(word("  indented", 8), ["indented"]);
+        assert_eq!(word("end   \nnext", 4), ["end", "next"]);
+        assert_eq!(word("one\n\ntwo", 10), ["one", "", "two"]);
+        assert_eq!(word("日本 語です", 5), ["日本", "語で", "す"]);
+    }
+
+    #[test]
+    fn truncation() {
+        assert_eq!(
+            lines("a long line\nshort", 6, WrapMode::Truncate),
+            ["a long", "short"]
+        );
+        assert_eq!(
+            lines("a long line\nshort", 6, WrapMode::Ellipsis),
+            ["a lon…", "short"]
+        );
+        assert_eq!(lines("日本語", 4, WrapMode::Ellipsis), ["日…"]);
+        assert_eq!(lines("日本語", 5, WrapMode::Truncate), ["日本"]);
+        assert_eq!(lines("abc", 0, WrapMode::Ellipsis), [""]);
+    }
+
+    #[test]
+    fn tab_expansion() {
+        let expand = |text: &str| expand_tabs(text.to_string());
+
+        assert_eq!(expand("a\tb"), "a   b");
+        assert_eq!(expand("\tx\n1234\ty"), "    x\n1234    y");
+        assert_eq!(expand("日\t."), "日  .");
+        assert_eq!(expand("no tabs"), "no tabs");
+    }
+
+    fn random_text(random: &mut Random) -> String {
+        const PIECES: [&str; 12] = [
+            "a",
+            "b",
+            "word",
+            "longerword",
+            " ",
+            " ",
+            "  ",
+            "\n",
+            "日",
+            "本",
+            "e\u{0301}",
+            "🦀",
+        ];
+
+        (0..random.below(40))
+            .map(|_| PIECES[random.below(PIECES.len())])
+            .collect()
+    }
+
+    fn visible(text: &str) -> String {
+        text.chars().filter(|c| !c.is_whitespace()).collect()
+    }
+
+    #[test]
+    fn no_line_exceeds_the_width() {
+        let mut random = Random::new(0x2545_f491_4f6c_dd1d);
+
+        for _ in 0..2000 {
+            let text = random_text(&mut random);
+            let width = 1 + random.below(12);
+
+            for wrap_mode in [
+                WrapMode::Hard,
+                WrapMode::Word,
+                WrapMode::Truncate,
+                WrapMode::Ellipsis,
+            ] {
+                for line in WrappedLines::new(&text, width, wrap_mode) {
+                    assert!(
+                        width::str_width(&line) <= width,
+                        "{wrap_mode:?} {line:?} is wider than {width} in {text:?}"
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn wrapping_does_not_lose_text() {
+        let mut random = Random::new(0x9e37_79b9_7f4a_7c15);
+
+        for _ in 0..2000 {
+            let text = random_text(&mut random);
+            // Wide enough for every character, otherwise they are dropped
+            let width = 2 + random.below(12);
+
+            let hard: String = lines(&text, width, WrapMode::Hard).concat();
+            assert_eq!(hard, text.replace('\n', ""), "width {width}");
+
+            let word = lines(&text, width, WrapMode::Word);
+            assert_eq!(visible(&word.concat()), visible(&text), "width {width}");
+
+            // Only the spaces where the lines are broken are trimmed
+            for line in &word {
+                assert!(
+                    !line.ends_with(' ')
+                        || text.contains(&format!("{line}\n"))
+                        || text.ends_with(line.as_str()),
+                    "{line:?} has trailing spaces in {text:?} with width {width}"
+                );
+            }
+
+            let truncated = lines(&text, width, WrapMode::Truncate);
+            let ellipsized = lines(&text, width, WrapMode::Ellipsis);
+            assert_eq!(truncated.len(), ellipsized.len());
+            for (line, source) in truncated.iter().zip(text.split('\n')) {
+                assert!(source.starts_with(line.as_str()));
+            }
+        }
+    }
+}
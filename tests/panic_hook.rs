@@ -0,0 +1,25 @@
+use std::sync::{Arc, Mutex};
+
+// Panic hooks are global, so this lives in its own test binary where nothing else panics
+
+#[test]
+fn panic_hook_delegates_to_the_previous_one() {
+    let messages = Arc::new(Mutex::new(Vec::new()));
+
+    let recorded = Arc::clone(&messages);
+    std::panic::set_hook(Box::new(move |info| {
+        let message = info.payload().downcast_ref::<&str>().copied();
+        recorded
+            .lock()
+            .unwrap()
+            .push(message.unwrap_or_default().to_string());
+    }));
+
+    // Without a terminal there is nothing to restore, the message still goes through
+    tinytui::install_panic_hook();
+    let result = std::panic::catch_unwind(|| panic!("boom"));
+
+    let _ = std::panic::take_hook();
+    assert!(result.is_err());
+    assert_eq!(*messages.lock().unwrap(), ["boom"]);
+}
@@ -0,0 +1,713 @@
+use tinytui::{
+    assert_frame, BorderType, Borders, Color, Constraint, Direction, HorizontalAlignment,
+    Rectangle, SortDirection, Spacing, Terminal, VerticalAlignment, Widget, WrapMode,
+};
+
+const ALIGNMENTS: [(VerticalAlignment, HorizontalAlignment); 9] = [
+    (VerticalAlignment::Top, HorizontalAlignment::Left),
+    (VerticalAlignment::Top, HorizontalAlignment::Center),
+    (VerticalAlignment::Top, HorizontalAlignment::Right),
+    (VerticalAlignment::Center, HorizontalAlignment::Left),
+    (VerticalAlignment::Center, HorizontalAlignment::Center),
+    (VerticalAlignment::Center, HorizontalAlignment::Right),
+    (VerticalAlignment::Bottom, HorizontalAlignment::Left),
+    (VerticalAlignment::Bottom, HorizontalAlignment::Center),
+    (VerticalAlignment::Bottom, HorizontalAlignment::Right),
+];
+
+/// Splits the area in a 3x3 grid, in the same order as `ALIGNMENTS`.
+fn grid(area: Rectangle) -> Vec<Rectangle> {
+    let (top, rest) = area.split_vertically_at(1.0 / 3.0);
+    let (middle, bottom) = rest.split_vertically();
+
+    [top, middle, bottom]
+        .into_iter()
+        .flat_map(|row| {
+            let (left, rest) = row.split_horizontally_at(1.0 / 3.0);
+            let (center, right) = rest.split_horizontally();
+            [left, center, right]
+        })
+        .collect()
+}
+
+#[test]
+fn rectangle() {
+    let mut terminal = Terminal::headless(30, 8);
+
+    let (left, right) = terminal.area().split_horizontally_at(0.4);
+    let (mut top, bottom) = right.split_vertically();
+
+    let mut left = left;
+    left.set_title(Some("[ Left ]".into()));
+    top.set_border_color(Color::Green);
+
+    left.render(&mut terminal);
+    top.render(&mut terminal);
+    bottom.render(&mut terminal);
+
+    assert_frame!(terminal, "rectangle");
+}
+
+#[test]
+fn text_alignments() {
+    let mut terminal = Terminal::headless(66, 24);
+
+    for (area, (vertical, horizontal)) in grid(terminal.area()).into_iter().zip(ALIGNMENTS) {
+        let mut text = area.text("Hello\nWorld!".to_string(), vertical, horizontal);
+        text.set_title(Some(format!("{vertical:?} {horizontal:?}")));
+        text.render(&mut terminal);
+    }
+
+    assert_frame!(terminal, "text_alignments");
+}
+
+#[test]
+fn text_hardwrapping() {
+    let mut terminal = Terminal::headless(16, 8);
+
+    let text = terminal.area().text(
+        "A long line that does not fit\nShort\n\nAfter an empty line".to_string(),
+        VerticalAlignment::Top,
+        HorizontalAlignment::Left,
+    );
+    text.render(&mut terminal);
+
+    assert_frame!(terminal, "text_hardwrapping");
+}
+
+#[test]
+fn item_list_alignments() {
+    let mut terminal = Terminal::headless(66, 24);
+
+    for (area, (vertical, horizontal)) in grid(terminal.area()).into_iter().zip(ALIGNMENTS) {
+        let mut item_list = area.item_list(
+            vec![
+                "First".to_string(),
+                "Second".to_string(),
+                "Third".to_string(),
+            ],
+            vertical,
+            horizontal,
+        );
+        item_list.set_title(Some(format!("{vertical:?} {horizontal:?}")));
+        item_list.set_border_color(Color::Green);
+        item_list.set_selected(Some(1));
+        item_list.render(&mut terminal);
+    }
+
+    assert_frame!(terminal, "item_list_alignments");
+}
+
+#[test]
+fn table_alignments() {
+    let mut terminal = Terminal::headless(66, 24);
+
+    for (area, (vertical, horizontal)) in grid(terminal.area()).into_iter().zip(ALIGNMENTS) {
+        let mut table = area.table(
+            vec![
+                vec!["Name".to_string(), "Size".to_string()],
+                vec!["tinytui".to_string(), "12".to_string()],
+                vec!["libc".to_string(), "4096".to_string()],
+            ],
+            vertical,
+            horizontal,
+        );
+        table.set_title(Some(format!("{vertical:?} {horizontal:?}")));
+        table.set_selected(Some(2));
+        table.render(&mut terminal);
+    }
+
+    assert_frame!(terminal, "table_alignments");
+}
+
+#[test]
+fn wide_characters() {
+    let mut terminal = Terminal::headless(40, 12);
+
+    let (left, right) = terminal.area().split_horizontally();
+    let (top, bottom) = right.split_vertically();
+
+    let mut text = left.text(
+        "日本語のテキスト\ncafe\u{301} 🦀 🇧🇷".to_string(),
+        VerticalAlignment::Top,
+        HorizontalAlignment::Right,
+    );
+    text.set_title(Some("[ 表題 ]".into()));
+    text.render(&mut terminal);
+
+    let mut item_list = top.item_list(
+        vec!["中文".to_string(), "ascii".to_string()],
+        VerticalAlignment::Top,
+        HorizontalAlignment::Center,
+    );
+    item_list.set_selected(Some(0));
+    item_list.render(&mut terminal);
+
+    let table = bottom.table(
+        vec![
+            vec!["名前".to_string(), "Size".to_string()],
+            vec!["crab 🦀".to_string(), "１２".to_string()],
+        ],
+        VerticalAlignment::Top,
+        HorizontalAlignment::Left,
+    );
+    table.render(&mut terminal);
+
+    assert_frame!(terminal, "wide_characters");
+}
+
+#[test]
+fn text_scrolling() {
+    let mut terminal = Terminal::headless(36, 7);
+
+    let lines: Vec<String> = (1..=12).map(|line| format!("Line {line}")).collect();
+    let (left, rest) = terminal.area().split_horizontally_at(1.0 / 3.0);
+    let (center, right) = rest.split_horizontally();
+
+    let mut top = left.text(
+        lines.join("\n"),
+        VerticalAlignment::Top,
+        HorizontalAlignment::Left,
+    );
+    top.set_scrollbar(true);
+    top.render(&mut terminal);
+
+    let mut middle = center.text(
+        lines.join("\n"),
+        VerticalAlignment::Top,
+        HorizontalAlignment::Left,
+    );
+    middle.set_scrollbar(true);
+    middle.scroll_down(2);
+    middle.page_down();
+    middle.scroll_up(1);
+    middle.render(&mut terminal);
+
+    let mut tail = right.text(
+        lines[..3].join("\n"),
+        VerticalAlignment::Bottom,
+        HorizontalAlignment::Left,
+    );
+    tail.set_scrollbar(true);
+    tail.set_follow_tail(true);
+    tail.change_text(Some(lines.join("\n")));
+    tail.render(&mut terminal);
+
+    assert_frame!(terminal, "text_scrolling");
+}
+
+#[test]
+fn text_wrap_modes() {
+    let mut terminal = Terminal::headless(56, 7);
+
+    let (left, right) = terminal.area().split_horizontally();
+    let (first, second) = left.split_horizontally();
+    let (third, fourth) = right.split_horizontally();
+
+    for (area, wrap_mode) in [first, second, third, fourth].into_iter().zip([
+        WrapMode::Hard,
+        WrapMode::Word,
+        WrapMode::Truncate,
+        WrapMode::Ellipsis,
+    ]) {
+        let mut text = area.text(
+            "The quick brown fox jumps\nover the lazy dog".to_string(),
+            VerticalAlignment::Top,
+            HorizontalAlignment::Left,
+        );
+        text.set_title(Some(format!("{wrap_mode:?}")));
+        text.set_wrap_mode(wrap_mode);
+        text.render(&mut terminal);
+    }
+
+    assert_frame!(terminal, "text_wrap_modes");
+}
+
+#[test]
+fn item_list_scrolling() {
+    let mut terminal = Terminal::headless(30, 6);
+
+    let items: Vec<String> = (1..=20).map(|item| format!("Item {item}")).collect();
+    let (left, right) = terminal.area().split_horizontally();
+
+    let mut top = left.item_list(
+        items.clone(),
+        VerticalAlignment::Top,
+        HorizontalAlignment::Left,
+    );
+    top.set_scrollbar(true);
+    top.select_next();
+    top.render(&mut terminal);
+
+    let mut scrolled = right.item_list(items, VerticalAlignment::Top, HorizontalAlignment::Left);
+    scrolled.set_scrollbar(true);
+    scrolled.set_selected(Some(9));
+    scrolled.page_down();
+    scrolled.render(&mut terminal);
+
+    assert_frame!(terminal, "item_list_scrolling");
+}
+
+#[test]
+fn table_header_and_separators() {
+    let mut terminal = Terminal::headless(60, 8);
+
+    let rows = vec![
+        vec!["tinytui".to_string(), "12".to_string(), "ok".to_string()],
+        vec!["libc".to_string(), "4096".to_string(), "ok".to_string()],
+    ];
+    let header = vec!["Name".to_string(), "Size".to_string(), "Status".to_string()];
+
+    let (left, right) = terminal.area().split_horizontally();
+
+    let mut plain = left.table(
+        rows.clone(),
+        VerticalAlignment::Top,
+        HorizontalAlignment::Left,
+    );
+    plain.set_header(Some(header.clone()));
+    plain.set_column_alignments(vec![HorizontalAlignment::Left, HorizontalAlignment::Right]);
+    plain.render(&mut terminal);
+
+    let mut separated = right.table(rows, VerticalAlignment::Top, HorizontalAlignment::Left);
+    separated.set_header(Some(header));
+    separated.set_column_separators(true);
+    separated.set_column_alignments(vec![
+        HorizontalAlignment::Left,
+        HorizontalAlignment::Right,
+        HorizontalAlignment::Center,
+    ]);
+    separated.set_selected(Some(1));
+    separated.render(&mut terminal);
+
+    assert_frame!(terminal, "table_header_and_separators");
+}
+
+#[test]
+fn table_column_widths() {
+    let mut terminal = Terminal::headless(60, 9);
+
+    let rows = vec![
+        vec![
+            "tinytui".to_string(),
+            "A tiny terminal user interface library".to_string(),
+            "12".to_string(),
+        ],
+        vec![
+            "libc".to_string(),
+            "Raw bindings to the platform's system libraries".to_string(),
+            "4096".to_string(),
+        ],
+    ];
+
+    let (left, right) = terminal.area().split_horizontally();
+
+    // The content does not fit, so the widest column is shrunk and cut with an ellipsis
+    let mut truncated = left.table(
+        rows.clone(),
+        VerticalAlignment::Top,
+        HorizontalAlignment::Left,
+    );
+    truncated.set_column_alignments(vec![
+        HorizontalAlignment::Left,
+        HorizontalAlignment::Left,
+        HorizontalAlignment::Right,
+    ]);
+    truncated.render(&mut terminal);
+
+    let mut wrapped = right.table(rows, VerticalAlignment::Top, HorizontalAlignment::Left);
+    wrapped.set_column_widths(vec![
+        Constraint::Max(4),
+        Constraint::Fill(1),
+        Constraint::Length(5),
+    ]);
+    wrapped.set_column_alignments(vec![
+        HorizontalAlignment::Left,
+        HorizontalAlignment::Left,
+        HorizontalAlignment::Right,
+    ]);
+    wrapped.set_cell_wrap_mode(WrapMode::Word);
+    wrapped.set_column_separators(true);
+    wrapped.set_selected(Some(1));
+    wrapped.render(&mut terminal);
+
+    assert_frame!(terminal, "table_column_widths");
+}
+
+#[test]
+fn table_wrapped_cells() {
+    let mut terminal = Terminal::headless(30, 11);
+
+    let rows = vec![
+        vec![
+            "tinytui".to_string(),
+            "A tiny terminal user interface library".to_string(),
+            "12".to_string(),
+        ],
+        vec![
+            "libc".to_string(),
+            "Raw bindings to the platform's system libraries".to_string(),
+            "4096".to_string(),
+        ],
+    ];
+
+    // Every line of the wrapped rows fits, words too long for the column are broken
+    let mut table = terminal
+        .area()
+        .table(rows, VerticalAlignment::Top, HorizontalAlignment::Left);
+    table.set_column_widths(vec![
+        Constraint::Max(4),
+        Constraint::Fill(1),
+        Constraint::Length(5),
+    ]);
+    table.set_column_alignments(vec![
+        HorizontalAlignment::Left,
+        HorizontalAlignment::Left,
+        HorizontalAlignment::Right,
+    ]);
+    table.set_cell_wrap_mode(WrapMode::Word);
+    table.set_column_separators(true);
+    table.render(&mut terminal);
+
+    assert_frame!(terminal, "table_wrapped_cells");
+}
+
+#[test]
+fn table_scrolling() {
+    let mut terminal = Terminal::headless(60, 8);
+
+    let rows: Vec<Vec<String>> = (1..=1000)
+        .map(|pid| {
+            vec![
+                pid.to_string(),
+                format!("worker-{pid}"),
+                format!("/usr/lib/worker --id {pid} --threads 4"),
+                format!("{}.{}", pid % 100, pid % 10),
+            ]
+        })
+        .collect();
+    let header = vec![
+        "PID".to_string(),
+        "Name".to_string(),
+        "Command".to_string(),
+        "CPU%".to_string(),
+    ];
+
+    let (left, right) = terminal.area().split_horizontally();
+
+    // The header stays in place while the rows scroll under it
+    let mut scrolled = left.table(
+        rows.clone(),
+        VerticalAlignment::Top,
+        HorizontalAlignment::Left,
+    );
+    scrolled.set_header(Some(header.clone()));
+    scrolled.set_scrollbar(true);
+    scrolled.set_selected(Some(499));
+    scrolled.page_down();
+    scrolled.render(&mut terminal);
+
+    // The command is too wide for the panel, so it's scrolled into view instead of cut
+    let mut wide = right.table(rows, VerticalAlignment::Top, HorizontalAlignment::Left);
+    wide.set_header(Some(header));
+    wide.set_column_separators(true);
+    wide.set_horizontal_scrolling(true);
+    wide.scroll_right(8);
+    wide.select_next();
+    wide.render(&mut terminal);
+
+    assert_frame!(terminal, "table_scrolling");
+}
+
+#[test]
+fn table_sorting() {
+    let mut terminal = Terminal::headless(60, 8);
+
+    let rows: Vec<Vec<String>> = [
+        ("1", "init", "0.5"),
+        ("842", "cargo", "12.25"),
+        ("77", "zsh", "2"),
+        ("1203", "vim", "2"),
+    ]
+    .iter()
+    .map(|(pid, name, cpu)| vec![pid.to_string(), name.to_string(), cpu.to_string()])
+    .collect();
+    let header = vec!["PID".to_string(), "Name".to_string(), "CPU%".to_string()];
+
+    let (left, right) = terminal.area().split_horizontally();
+
+    let mut by_pid = left.table(
+        rows.clone(),
+        VerticalAlignment::Top,
+        HorizontalAlignment::Left,
+    );
+    by_pid.set_header(Some(header.clone()));
+    by_pid.sort_by(0, SortDirection::Ascending);
+    by_pid.render(&mut terminal);
+
+    // The selection stays on the same process when the order changes
+    let mut by_cpu = right.table(rows, VerticalAlignment::Top, HorizontalAlignment::Left);
+    by_cpu.set_header(Some(header));
+    by_cpu.set_column_alignments(vec![
+        HorizontalAlignment::Right,
+        HorizontalAlignment::Left,
+        HorizontalAlignment::Right,
+    ]);
+    by_cpu.set_selected(Some(0));
+    by_cpu.sort_by(2, SortDirection::Descending);
+    by_cpu.render(&mut terminal);
+
+    assert_frame!(terminal, "table_sorting");
+}
+
+#[test]
+fn constraint_layout() {
+    let mut terminal = Terminal::headless(40, 10);
+
+    // A fixed sidebar and a status bar with a single line of text, the rest is split in thirds
+    let mut rows = terminal.area().split(
+        Direction::Vertical,
+        &[Constraint::Fill(1), Constraint::Length(3)],
+    );
+    let status_bar = rows.pop().unwrap();
+    let main = rows.pop().unwrap();
+
+    let mut columns = main.split(
+        Direction::Horizontal,
+        &[Constraint::Length(10), Constraint::Fill(1)],
+    );
+    let content = columns.pop().unwrap();
+    let sidebar = columns.pop().unwrap();
+
+    sidebar
+        .item_list(
+            vec!["One".to_string(), "Two".to_string()],
+            VerticalAlignment::Top,
+            HorizontalAlignment::Left,
+        )
+        .render(&mut terminal);
+
+    for (i, part) in content
+        .split(
+            Direction::Horizontal,
+            &[
+                Constraint::Ratio(1, 3),
+                Constraint::Ratio(1, 3),
+                Constraint::Ratio(1, 3),
+            ],
+        )
+        .into_iter()
+        .enumerate()
+    {
+        part.text(
+            format!("{}/3", i + 1),
+            VerticalAlignment::Center,
+            HorizontalAlignment::Center,
+        )
+        .render(&mut terminal);
+    }
+
+    status_bar
+        .text(
+            "Ready".to_string(),
+            VerticalAlignment::Top,
+            HorizontalAlignment::Left,
+        )
+        .render(&mut terminal);
+
+    assert_frame!(terminal, "constraint_layout");
+}
+
+#[test]
+fn grid_layout() {
+    let mut terminal = Terminal::headless(40, 10);
+
+    let mut grid = terminal.area().grid(
+        &[Constraint::Length(3), Constraint::Fill(1)],
+        &[
+            Constraint::Length(12),
+            Constraint::Fill(1),
+            Constraint::Fill(1),
+        ],
+    );
+    grid.set_areas(&["header header header", "menu   main   logs"]);
+
+    for name in ["header", "menu", "main", "logs"] {
+        grid.area(name)
+            .unwrap()
+            .text(
+                name.to_string(),
+                VerticalAlignment::Center,
+                HorizontalAlignment::Center,
+            )
+            .render(&mut terminal);
+    }
+
+    assert_frame!(terminal, "grid_layout");
+}
+
+#[test]
+fn borders_padding_and_margin() {
+    let mut terminal = Terminal::headless(60, 8);
+
+    let mut parts = terminal.area().split(
+        Direction::Horizontal,
+        &[
+            Constraint::Fill(1),
+            Constraint::Fill(1),
+            Constraint::Fill(1),
+        ],
+    );
+    let right = parts.pop().unwrap();
+    let middle = parts.pop().unwrap();
+    let left = parts.pop().unwrap();
+
+    let mut padded = left.text(
+        "Padded text that wraps".to_string(),
+        VerticalAlignment::Top,
+        HorizontalAlignment::Left,
+    );
+    padded.set_title(Some("Padding".into()));
+    padded.set_padding(Spacing::symmetric(1, 2));
+    padded.render(&mut terminal);
+
+    let mut list = middle.item_list(
+        vec!["One".to_string(), "Two".to_string(), "Three".to_string()],
+        VerticalAlignment::Top,
+        HorizontalAlignment::Left,
+    );
+    list.set_title(Some("Margin".into()));
+    list.set_margin(Spacing::uniform(1));
+    list.set_borders(Borders::TOP | Borders::BOTTOM);
+    list.set_selected(Some(1));
+    list.render(&mut terminal);
+
+    // The separators reach the borders that are there
+    let mut table = right.table(
+        vec![
+            vec!["a".to_string(), "1".to_string()],
+            vec!["b".to_string(), "2".to_string()],
+        ],
+        VerticalAlignment::Top,
+        HorizontalAlignment::Left,
+    );
+    table.set_header(Some(vec!["Name".to_string(), "Size".to_string()]));
+    table.set_column_separators(true);
+    table.set_borders(Borders::LEFT | Borders::BOTTOM);
+    table.render(&mut terminal);
+
+    assert_frame!(terminal, "borders_padding_and_margin");
+}
+
+#[test]
+fn border_types() {
+    let mut terminal = Terminal::headless(60, 12);
+
+    let grid = terminal.area().grid(
+        &[Constraint::Fill(1), Constraint::Fill(1)],
+        &[
+            Constraint::Fill(1),
+            Constraint::Fill(1),
+            Constraint::Fill(1),
+        ],
+    );
+
+    let border_types = [
+        BorderType::Plain,
+        BorderType::Rounded,
+        BorderType::Double,
+        BorderType::Thick,
+        BorderType::Dashed,
+    ];
+    for (i, border_type) in border_types.into_iter().enumerate() {
+        let mut area = grid.cell(i / 3, i % 3);
+        area.set_title(Some(format!("{border_type:?}")));
+        area.set_border_type(border_type);
+        area.render(&mut terminal);
+    }
+
+    // The table lines and the scrollbar follow the border
+    let rows = (1..=5)
+        .map(|row| vec![row.to_string(), format!("Row {row}")])
+        .collect();
+    let mut table = grid
+        .cell(1, 2)
+        .table(rows, VerticalAlignment::Top, HorizontalAlignment::Left);
+    table.set_title(Some("Ascii".into()));
+    table.set_border_type(BorderType::Ascii);
+    table.set_header(Some(vec!["#".to_string(), "Name".to_string()]));
+    table.set_column_separators(true);
+    table.set_scrollbar(true);
+    table.render(&mut terminal);
+
+    assert_frame!(terminal, "border_types");
+}
+
+#[test]
+fn merged_borders() {
+    let mut terminal = Terminal::headless(60, 12);
+
+    let mut screen = terminal.area();
+    screen.set_merged_borders(true);
+
+    let mut columns = screen.split(
+        Direction::Horizontal,
+        &[Constraint::Length(20), Constraint::Fill(1)],
+    );
+    let right = columns.pop().unwrap();
+    let left = columns.pop().unwrap();
+
+    // The nested splits share the walls of their parent as well
+    let mut rows = right.split(
+        Direction::Vertical,
+        &[Constraint::Fill(1), Constraint::Fill(1)],
+    );
+    let bottom = rows.pop().unwrap();
+    let top = rows.pop().unwrap();
+
+    let mut bottom_columns = bottom.split(
+        Direction::Horizontal,
+        &[Constraint::Fill(1), Constraint::Fill(1)],
+    );
+    let bottom_right = bottom_columns.pop().unwrap();
+    let bottom_left = bottom_columns.pop().unwrap();
+
+    let mut menu = left.item_list(
+        vec!["Processes".to_string(), "Logs".to_string()],
+        VerticalAlignment::Top,
+        HorizontalAlignment::Left,
+    );
+    menu.set_title(Some("Menu".into()));
+    menu.set_selected(Some(0));
+    menu.render(&mut terminal);
+
+    let mut table = top.table(
+        vec![
+            vec!["1".to_string(), "init".to_string()],
+            vec!["842".to_string(), "cargo".to_string()],
+        ],
+        VerticalAlignment::Top,
+        HorizontalAlignment::Left,
+    );
+    table.set_header(Some(vec!["PID".to_string(), "Name".to_string()]));
+    table.set_column_separators(true);
+    table.render(&mut terminal);
+
+    bottom_left
+        .text(
+            "Left".to_string(),
+            VerticalAlignment::Center,
+            HorizontalAlignment::Center,
+        )
+        .render(&mut terminal);
+
+    let mut rounded = bottom_right.text(
+        "Right".to_string(),
+        VerticalAlignment::Center,
+        HorizontalAlignment::Center,
+    );
+    rounded.set_border_type(BorderType::Rounded);
+    rounded.render(&mut terminal);
+
+    assert_frame!(terminal, "merged_borders");
+}